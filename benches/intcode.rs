@@ -0,0 +1,299 @@
+//! Compares the interpreter with and without the decode cache against the
+//! interpreter it replaced, and times the registered solutions of the Intcode
+//! days.
+//!
+//! Run with `cargo bench`. The programs are shaped like the day 2, 5 and 7
+//! puzzle inputs, so no personal input files are needed.
+
+use std::collections::VecDeque;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use aoc_2019::days;
+use aoc_2019::intcode::decode::{Instruction, Parameter};
+use aoc_2019::intcode::{Computer, Memory, Status};
+
+/// Day 5 example comparing the input to 8, taking the most branches.
+const DAY5: [i64; 47] = [
+    3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0, 0,
+    1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4, 20, 1105,
+    1, 46, 98, 99,
+];
+
+/// Day 7 example amplifier for the feedback loop.
+const DAY7: [i64; 29] = [
+    3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1, 28, 1005,
+    28, 6, 99, 0, 0, 5,
+];
+
+#[derive(Debug, Copy, Clone)]
+enum Interpreter {
+    /// Decodes every instruction into a freshly allocated `Instruction`.
+    Baseline,
+    Uncached,
+    Cached,
+}
+
+fn main() {
+    let day2 = day2_program();
+    for &interpreter in &[
+        Interpreter::Baseline,
+        Interpreter::Uncached,
+        Interpreter::Cached,
+    ] {
+        let name = format!("{:?}", interpreter).to_lowercase();
+        bench(&format!("day 2 noun/verb search, {}", name), || {
+            day2_search(&day2, interpreter)
+        });
+        bench(&format!("day 5 diagnostics, {}", name), || {
+            (7..=9).map(|input| day5(input, interpreter)).sum()
+        });
+        bench(&format!("day 7 feedback loop, {}", name), || {
+            permutations(&[5, 6, 7, 8, 9])
+                .iter()
+                .map(|phases| day7(phases, interpreter))
+                .max()
+                .unwrap()
+        });
+    }
+    for &(day, part, program) in &[(5, 1, &DAY5[..]), (5, 2, &DAY5[..]), (7, 2, &DAY7[..])] {
+        let input = program
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let solver = days::solver(day).expect("Day is not registered");
+        bench(&format!("day {} part {} solver", day, part), || {
+            let answer = solver.solve(part, &input).expect("No solution");
+            answer.parse().expect("Answer is not a number")
+        });
+    }
+}
+
+/// Runs `f` in ten batches of about 100ms each and reports the mean time per
+/// run of the fastest batch, the one least disturbed by other load.
+fn bench(name: &str, mut f: impl FnMut() -> i64) {
+    black_box(f());
+    let fastest = (0..10)
+        .map(|_| {
+            let start = Instant::now();
+            let mut runs = 0;
+            while start.elapsed() < Duration::from_millis(100) {
+                black_box(f());
+                runs += 1;
+            }
+            start.elapsed() / runs
+        })
+        .min()
+        .unwrap();
+    println!("{:<40} {:>12.1?}/run", name, fastest);
+}
+
+enum Machine {
+    Baseline(Baseline),
+    Computer(Computer),
+}
+
+impl Machine {
+    fn new(interpreter: Interpreter, program: &[i64], input: &[i64]) -> Self {
+        let mut machine = match interpreter {
+            Interpreter::Baseline => Machine::Baseline(Baseline::new(program)),
+            Interpreter::Uncached | Interpreter::Cached => {
+                let mut computer = Computer::new(program);
+                computer.set_decode_cache(matches!(interpreter, Interpreter::Cached));
+                Machine::Computer(computer)
+            }
+        };
+        for value in input {
+            machine.push_input(*value);
+        }
+        machine
+    }
+
+    fn push_input(&mut self, value: i64) {
+        match self {
+            Machine::Baseline(baseline) => baseline.input.push_back(value),
+            Machine::Computer(computer) => computer.push_input(value),
+        }
+    }
+
+    fn resume(&mut self) -> Status {
+        match self {
+            Machine::Baseline(baseline) => baseline.resume(),
+            Machine::Computer(computer) => computer.resume().expect("Execution failed"),
+        }
+    }
+
+    fn memory(&self) -> &Memory<i64> {
+        match self {
+            Machine::Baseline(baseline) => &baseline.memory,
+            Machine::Computer(computer) => computer.memory(),
+        }
+    }
+}
+
+/// The interpreter as it was before the decode cache, on the same memory.
+struct Baseline {
+    memory: Memory<i64>,
+    instruction_pointer: usize,
+    relative_base: i64,
+    input: VecDeque<i64>,
+}
+
+impl Baseline {
+    fn new(program: &[i64]) -> Self {
+        let mut memory = Memory::default();
+        memory.load(program);
+        Baseline {
+            memory,
+            instruction_pointer: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+        }
+    }
+
+    fn resume(&mut self) -> Status {
+        loop {
+            let ip = self.instruction_pointer;
+            let words = [
+                self.memory.get(ip),
+                self.memory.get(ip + 1),
+                self.memory.get(ip + 2),
+                self.memory.get(ip + 3),
+            ];
+            let Instruction {
+                opcode,
+                parameters,
+                length,
+            } = Instruction::decode(&words).expect("Invalid instruction");
+            let mut next_instruction = ip + length;
+            match opcode {
+                1 => self.store(
+                    &parameters[2],
+                    self.load(&parameters[0]) + self.load(&parameters[1]),
+                ),
+                2 => self.store(
+                    &parameters[2],
+                    self.load(&parameters[0]) * self.load(&parameters[1]),
+                ),
+                3 => match self.input.pop_front() {
+                    Some(value) => self.store(&parameters[0], value),
+                    None => return Status::NeedsInput,
+                },
+                4 => {
+                    self.instruction_pointer = next_instruction;
+                    return Status::Output(self.load(&parameters[0]));
+                }
+                5 | 6 => {
+                    if (self.load(&parameters[0]) != 0) == (opcode == 5) {
+                        next_instruction = self.load(&parameters[1]) as usize;
+                    }
+                }
+                7 => self.store(
+                    &parameters[2],
+                    (self.load(&parameters[0]) < self.load(&parameters[1])) as i64,
+                ),
+                8 => self.store(
+                    &parameters[2],
+                    (self.load(&parameters[0]) == self.load(&parameters[1])) as i64,
+                ),
+                9 => self.relative_base += self.load(&parameters[0]),
+                _ => return Status::Halted,
+            }
+            self.instruction_pointer = next_instruction;
+        }
+    }
+
+    fn address(&self, parameter: &Parameter) -> usize {
+        match parameter {
+            Parameter::Address { address } => *address,
+            Parameter::Relative { offset } => (self.relative_base + offset) as usize,
+            Parameter::Value { .. } => panic!("Parameter has no address"),
+        }
+    }
+
+    fn load(&self, parameter: &Parameter) -> i64 {
+        match parameter {
+            Parameter::Value { value } => *value,
+            _ => self.memory.get(self.address(parameter)),
+        }
+    }
+
+    fn store(&mut self, parameter: &Parameter, value: i64) {
+        let address = self.address(parameter);
+        self.memory
+            .set(address, value)
+            .expect("Memory limit exceeded");
+    }
+}
+
+/// A chain of additions and multiplications reading the cells noun and verb
+/// point to, like the gravity assist programs of day 2.
+fn day2_program() -> Vec<i64> {
+    let mut program = vec![1, 0, 0, 3];
+    for index in 0..32 {
+        let opcode = if index % 4 == 3 { 2 } else { 1 };
+        program.extend_from_slice(&[opcode, 3, 200 + index % 8, 3]);
+    }
+    program.extend_from_slice(&[1, 3, 0, 0, 99]);
+    program.resize(200, 0);
+    program.extend(1..=8);
+    program
+}
+
+fn day2_search(program: &[i64], interpreter: Interpreter) -> i64 {
+    let mut program = program.to_vec();
+    let mut sum = 0;
+    for noun in 0..=99 {
+        for verb in 0..=99 {
+            program[1] = noun;
+            program[2] = verb;
+            let mut machine = Machine::new(interpreter, &program, &[]);
+            assert_eq!(machine.resume(), Status::Halted);
+            sum += machine.memory().get(0) % 1000;
+        }
+    }
+    sum
+}
+
+fn day5(input: i64, interpreter: Interpreter) -> i64 {
+    match Machine::new(interpreter, &DAY5, &[input]).resume() {
+        Status::Output(value) => value,
+        status => panic!("Unexpected {:?}", status),
+    }
+}
+
+fn day7(phases: &[i64], interpreter: Interpreter) -> i64 {
+    let mut amplifiers: Vec<Machine> = phases
+        .iter()
+        .map(|phase| Machine::new(interpreter, &DAY7, &[*phase]))
+        .collect();
+    let mut signal = 0;
+    loop {
+        for (index, amplifier) in amplifiers.iter_mut().enumerate() {
+            amplifier.push_input(signal);
+            match amplifier.resume() {
+                Status::Output(value) => signal = value,
+                Status::Halted if index == phases.len() - 1 => return signal,
+                Status::Halted => {}
+                Status::NeedsInput => panic!("Amplifier is missing input"),
+            }
+        }
+    }
+}
+
+fn permutations(items: &[i64]) -> Vec<Vec<i64>> {
+    if items.is_empty() {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(index);
+        for mut permutation in permutations(&rest) {
+            permutation.insert(0, *item);
+            result.push(permutation);
+        }
+    }
+    result
+}
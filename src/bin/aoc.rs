@@ -0,0 +1,253 @@
+use std::env;
+use std::fs;
+use std::io::{stdin, Read};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Instant;
+
+use aoc_2019::days::answers::Answers;
+use aoc_2019::days::day2::{self, Day2};
+use aoc_2019::days::day3::{self, render, Day3};
+use aoc_2019::days::{self, Solution, Solver, SOLVERS};
+
+const USAGE: &str = "Usage: aoc run <day> [--part 1|2] [--input <file>]
+       aoc run 2 [--formula] [--input <file>]
+       aoc run 3 [--intersections] [--min-wires <count>] [--render] [--svg <file>]
+                 [--input <file>]
+       aoc verify [--answers <directory>]";
+
+/// Solves the puzzles of a day, reading the puzzle input from stdin unless a
+/// file is given, or checks all days against the answers in `answers/`.
+///
+/// Exits with 2 on invalid arguments and 1 if a part fails.
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
+    let succeeded = match command {
+        Command::Run {
+            day,
+            solver,
+            parts,
+            input,
+            formula,
+            wires,
+        } => run(day, solver, &parts, input.as_deref(), formula, &wires),
+        Command::Verify { answers } => verify(&answers),
+    };
+    if !succeeded {
+        process::exit(1);
+    }
+}
+
+enum Command {
+    Run {
+        day: u8,
+        solver: &'static dyn Solver,
+        parts: Vec<u8>,
+        input: Option<String>,
+        /// Prints the output of the day 2 program in terms of noun and verb.
+        formula: bool,
+        wires: WireOptions,
+    },
+    Verify {
+        answers: PathBuf,
+    },
+}
+
+/// Additional output for the wires of day 3.
+#[derive(Default)]
+struct WireOptions {
+    /// Lists the intersections and points where this many wires meet.
+    min_wires: Option<usize>,
+    render: bool,
+    svg: Option<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter().map(String::as_str);
+    match args.next() {
+        Some("run") => parse_run_args(args),
+        Some("verify") => {
+            let mut answers = PathBuf::from("answers");
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--answers" => answers = args.next().ok_or("Missing answers directory")?.into(),
+                    other => return Err(format!("Unknown argument {}", other)),
+                }
+            }
+            Ok(Command::Verify { answers })
+        }
+        Some(other) => Err(format!("Unknown command {}", other)),
+        None => Err("Missing command".to_string()),
+    }
+}
+
+fn parse_run_args<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<Command, String> {
+    let day = args.next().ok_or("Missing day")?;
+    let day = day.parse().map_err(|_| format!("Invalid day {}", day))?;
+    let mut parts = vec![1, 2];
+    let mut input = None;
+    let mut formula = false;
+    let mut wires = WireOptions::default();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--part" => {
+                let part = args.next().ok_or("Missing part")?;
+                parts = match part {
+                    "1" => vec![1],
+                    "2" => vec![2],
+                    _ => return Err(format!("Invalid part {}, expected 1 or 2", part)),
+                };
+            }
+            "--input" => input = Some(args.next().ok_or("Missing input file")?.to_string()),
+            "--formula" if day == 2 => formula = true,
+            "--intersections" if day == 3 => wires.min_wires = wires.min_wires.or(Some(3)),
+            "--min-wires" if day == 3 => {
+                let count = args.next().ok_or("Missing wire count")?;
+                wires.min_wires = Some(
+                    count
+                        .parse()
+                        .map_err(|_| format!("Invalid wire count {}", count))?,
+                );
+            }
+            "--render" if day == 3 => wires.render = true,
+            "--svg" if day == 3 => {
+                wires.svg = Some(args.next().ok_or("Missing SVG file")?.to_string())
+            }
+            other => return Err(format!("Unknown argument {}", other)),
+        }
+    }
+    let solver = days::solver(day).map_err(|error| error.to_string())?;
+    Ok(Command::Run {
+        day,
+        solver,
+        parts,
+        input,
+        formula,
+        wires,
+    })
+}
+
+fn run(
+    day: u8,
+    solver: &dyn Solver,
+    parts: &[u8],
+    input: Option<&str>,
+    formula: bool,
+    wires: &WireOptions,
+) -> bool {
+    let input = match read_input(input) {
+        Ok(input) => input,
+        Err(error) => {
+            eprintln!("Could not read input: {}", error);
+            return false;
+        }
+    };
+    let mut succeeded = true;
+    for &part in parts {
+        let start = Instant::now();
+        let result = solver.solve(part, &input);
+        let elapsed = start.elapsed();
+        match result {
+            Ok(answer) => println!("Day {} part {}: {} ({:.3?})", day, part, answer, elapsed),
+            Err(error) => {
+                eprintln!("Day {} part {} failed: {}", day, part, error);
+                succeeded = false;
+            }
+        }
+    }
+    if formula {
+        if let Err(error) = show_formula(&input) {
+            eprintln!("Could not derive formula: {}", error);
+            succeeded = false;
+        }
+    }
+    if let Err(error) = show_wires(&input, wires) {
+        eprintln!("Could not show wires: {}", error);
+        succeeded = false;
+    }
+    succeeded
+}
+
+fn show_formula(input: &str) -> Result<(), String> {
+    let program = Day2.parse(input).map_err(|error| error.to_string())?;
+    match day2::output_formula(&program).map_err(|error| error.to_string())? {
+        Some(formula) => println!("Output: {}", formula),
+        None => println!("Output depends on the path taken"),
+    }
+    Ok(())
+}
+
+fn show_wires(input: &str, options: &WireOptions) -> Result<(), String> {
+    if let Some(min_wires) = options.min_wires {
+        let report =
+            day3::intersection_report(input, min_wires).map_err(|error| error.to_string())?;
+        print!("{}", report);
+    }
+    if !options.render && options.svg.is_none() {
+        return Ok(());
+    }
+    let wires = Day3.parse(input).map_err(|error| error.to_string())?;
+    if options.render {
+        print!("{}", render::render_ascii(&wires, 120, 60));
+    }
+    if let Some(path) = &options.svg {
+        fs::write(path, render::render_svg(&wires)).map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+/// Checks every registered day against its answers file in `directory`, a
+/// missing file counts as a failure.
+fn verify(directory: &Path) -> bool {
+    let (mut passed, mut failed) = (0, 0);
+    for (day, _) in SOLVERS.iter() {
+        let path = Answers::path(directory, *day);
+        if !path.exists() {
+            println!("Day {}: FAIL, no answers in {}", day, path.display());
+            failed += 1;
+            continue;
+        }
+        let checks = Answers::load(*day, &path).and_then(|answers| answers.verify());
+        match checks {
+            Ok(checks) => {
+                for check in checks {
+                    println!("{}", check);
+                    if check.passed() {
+                        passed += 1;
+                    } else {
+                        failed += 1;
+                    }
+                }
+            }
+            Err(error) => {
+                println!(
+                    "Day {}: could not verify {}: {}",
+                    day,
+                    path.display(),
+                    error
+                );
+                failed += 1;
+            }
+        }
+    }
+    println!("{} passed, {} failed", passed, failed);
+    failed == 0
+}
+
+fn read_input(path: Option<&str>) -> std::io::Result<String> {
+    match path {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut input = String::new();
+            stdin().lock().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
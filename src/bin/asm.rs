@@ -0,0 +1,25 @@
+use std::io::Read;
+use std::process;
+
+use aoc_2019::intcode::asm::assemble;
+
+fn main() {
+    let mut source = String::new();
+    std::io::stdin()
+        .read_to_string(&mut source)
+        .expect("Could not read source");
+    match assemble(&source) {
+        Ok(program) => println!(
+            "{}",
+            program
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    }
+}
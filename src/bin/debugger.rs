@@ -0,0 +1,33 @@
+use std::env;
+use std::io::{stdin, stdout, BufRead, Write};
+
+use aoc_2019::intcode::debugger::{Command, Debugger};
+use aoc_2019::intcode::io::read_program_file;
+
+fn main() {
+    let path = env::args().nth(1).expect("Missing program file argument");
+    let program = read_program_file(&path).expect("Could not read program");
+    let mut debugger = Debugger::new(&program);
+    println!(
+        "Loaded {} words from {}, type `help` for commands",
+        program.len(),
+        path
+    );
+    prompt();
+    for line in stdin().lock().lines() {
+        let line = line.expect("Could not read command");
+        if !line.trim().is_empty() {
+            match Command::parse(&line) {
+                Ok(Command::Quit) => break,
+                Ok(command) => print!("{}", debugger.execute(command)),
+                Err(message) => println!("{}", message),
+            }
+        }
+        prompt();
+    }
+}
+
+fn prompt() {
+    print!("(intdbg) ");
+    stdout().flush().expect("Could not write prompt");
+}
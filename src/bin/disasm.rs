@@ -0,0 +1,7 @@
+use aoc_2019::intcode::disasm::disassemble;
+use aoc_2019::intcode::io::read_program;
+
+fn main() {
+    let program = read_program().expect("Could not read program");
+    print!("{}", disassemble(&program));
+}
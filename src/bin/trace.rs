@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::env;
+use std::io::{stdout, BufWriter};
+use std::rc::Rc;
+
+use aoc_2019::intcode::io::read_program_file;
+use aoc_2019::intcode::trace::{BinaryTracer, JsonTracer, LogTracer, Tracer};
+use aoc_2019::intcode::Computer;
+
+/// Runs a program with the given input values and writes its trace to stdout.
+///
+/// Usage: trace [--log|--json|--binary] <program> [input...]
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let format = match args.first().map(String::as_str) {
+        Some(flag) if flag.starts_with("--") => args.remove(0),
+        _ => "--log".to_string(),
+    };
+    let path = args.first().expect("Missing program file argument");
+    let program = read_program_file(path).expect("Could not read program");
+    let input = args[1..]
+        .iter()
+        .map(|value| value.parse().expect("Invalid input value"))
+        .collect();
+
+    let sink = BufWriter::new(stdout());
+    match format.as_str() {
+        "--log" => {
+            let tracer = run(&program, input, LogTracer::new(sink));
+            tracer.finish().expect("Could not write trace");
+        }
+        "--json" => {
+            let tracer = run(&program, input, JsonTracer::new(sink));
+            tracer.finish().expect("Could not write trace");
+        }
+        "--binary" => {
+            let tracer = run(&program, input, BinaryTracer::new(sink));
+            tracer.finish().expect("Could not write trace");
+        }
+        other => panic!("Unknown trace format {}", other),
+    }
+}
+
+fn run<T: Tracer<i64> + 'static>(program: &[i64], input: Vec<i64>, tracer: T) -> T {
+    let tracer = Rc::new(RefCell::new(tracer));
+    let mut computer = Computer::new(program);
+    for value in input {
+        computer.push_input(value);
+    }
+    computer.set_tracer(Box::new(tracer.clone()));
+    if let Err(error) = computer.run() {
+        eprintln!("Execution failed: {}", error);
+    }
+    drop(computer);
+    Rc::try_unwrap(tracer)
+        .ok()
+        .expect("Tracer is still in use")
+        .into_inner()
+}
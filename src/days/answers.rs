@@ -0,0 +1,244 @@
+//! Expected answers for checking the solutions against known results.
+//!
+//! The answers of a day are stored in `day<N>.txt`, one field per line:
+//!
+//! ```text
+//! input ../inputs/day5.txt
+//! part1 15508323
+//! part2 9006327
+//! ```
+//!
+//! The input path is relative to the answers file. Parts without an expected
+//! answer are not checked.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use super::solver;
+
+#[derive(Debug)]
+pub enum Error {
+    IoError { error: std::io::Error },
+    InvalidLine { line: usize },
+    MissingField { name: &'static str },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::IoError { error } => write!(f, "{}", error),
+            Error::InvalidLine { line } => write!(f, "invalid answers line {}", line),
+            Error::MissingField { name } => write!(f, "answers lack {}", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::IoError { error }
+    }
+}
+
+/// Input and expected answers of a day.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Answers {
+    pub day: u8,
+    pub input: PathBuf,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// Result of checking one part.
+#[derive(Debug)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Passed,
+    Failed { expected: String, actual: String },
+    Error { error: super::Error },
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        matches!(self.outcome, Outcome::Passed)
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day {} part {}: ", self.day, self.part)?;
+        match &self.outcome {
+            Outcome::Passed => write!(f, "pass")?,
+            Outcome::Failed { expected, actual } => {
+                write!(f, "FAIL, expected {} but got {}", expected, actual)?
+            }
+            Outcome::Error { error } => write!(f, "ERROR, {}", error)?,
+        }
+        write!(f, " ({:.3?})", self.elapsed)
+    }
+}
+
+impl Answers {
+    /// Path of the answers file of `day` in `directory`.
+    pub fn path(directory: impl AsRef<Path>, day: u8) -> PathBuf {
+        directory.as_ref().join(format!("day{}.txt", day))
+    }
+
+    /// Loads the answers of `day` from `path`, resolving the input path
+    /// against the directory of the file.
+    pub fn load(day: u8, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut answers = Self::parse(day, &fs::read_to_string(path)?)?;
+        if let Some(directory) = path.parent() {
+            answers.input = directory.join(answers.input);
+        }
+        Ok(answers)
+    }
+
+    pub fn parse(day: u8, text: &str) -> Result<Self, Error> {
+        let mut input = None;
+        let mut part1 = None;
+        let mut part2 = None;
+        for (index, text) in text.lines().enumerate() {
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let (key, value) = match text.find(' ') {
+                Some(index) => (&text[..index], text[index + 1..].trim()),
+                None => return Err(Error::InvalidLine { line: index + 1 }),
+            };
+            match key {
+                "input" => input = Some(PathBuf::from(value)),
+                "part1" => part1 = Some(value.to_string()),
+                "part2" => part2 = Some(value.to_string()),
+                _ => return Err(Error::InvalidLine { line: index + 1 }),
+            }
+        }
+        Ok(Answers {
+            day,
+            input: input.ok_or(Error::MissingField { name: "input" })?,
+            part1,
+            part2,
+        })
+    }
+
+    /// Reads the input and checks the parts with an expected answer.
+    pub fn verify(&self) -> Result<Vec<Check>, Error> {
+        Ok(self.check(&fs::read_to_string(&self.input)?))
+    }
+
+    /// Checks the parts with an expected answer against `input`.
+    pub fn check(&self, input: &str) -> Vec<Check> {
+        [(1, &self.part1), (2, &self.part2)]
+            .iter()
+            .filter_map(|(part, expected)| {
+                expected
+                    .as_ref()
+                    .map(|expected| check(self.day, *part, input, expected))
+            })
+            .collect()
+    }
+}
+
+/// Runs `part` of `day` on `input` and compares its answer with `expected`.
+pub fn check(day: u8, part: u8, input: &str, expected: &str) -> Check {
+    let start = Instant::now();
+    let result = solver(day).and_then(|solver| solver.solve(part, input));
+    let elapsed = start.elapsed();
+    let outcome = match result {
+        Ok(actual) if actual == expected => Outcome::Passed,
+        Ok(actual) => Outcome::Failed {
+            expected: expected.to_string(),
+            actual,
+        },
+        Err(error) => Outcome::Error { error },
+    };
+    Check {
+        day,
+        part,
+        outcome,
+        elapsed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_answers() {
+        let answers = Answers::parse(5, "input day5.txt\n\npart1 42\n").unwrap();
+        assert_eq!(
+            answers,
+            Answers {
+                day: 5,
+                input: PathBuf::from("day5.txt"),
+                part1: Some("42".to_string()),
+                part2: None,
+            }
+        );
+        assert!(matches!(
+            Answers::parse(5, "part1 42"),
+            Err(Error::MissingField { name: "input" })
+        ));
+        assert!(matches!(
+            Answers::parse(5, "input day5.txt\npart3 1"),
+            Err(Error::InvalidLine { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn reports_failures_and_errors() {
+        let answers = Answers {
+            day: 1,
+            input: PathBuf::new(),
+            part1: Some("2".to_string()),
+            part2: Some("3".to_string()),
+        };
+        let checks = answers.check("14");
+        assert!(checks[0].passed());
+        assert!(matches!(
+            &checks[1].outcome,
+            Outcome::Failed { expected, actual } if expected == "3" && actual == "2"
+        ));
+        assert!(checks[1]
+            .to_string()
+            .starts_with("Day 1 part 2: FAIL, expected 3 but got 2 ("));
+        assert!(matches!(
+            check(9, 1, "", "1").outcome,
+            Outcome::Error {
+                error: crate::days::Error::UnknownDay { day: 9 }
+            }
+        ));
+    }
+
+    #[test]
+    fn verifies_input_next_to_answers() {
+        let directory = std::env::temp_dir().join(format!("aoc-answers-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("masses.txt"), "12\n14\n").unwrap();
+        fs::write(
+            Answers::path(&directory, 1),
+            "input masses.txt\npart1 4\npart2 4\n",
+        )
+        .unwrap();
+        let checks = Answers::load(1, Answers::path(&directory, 1))
+            .unwrap()
+            .verify();
+        fs::remove_dir_all(&directory).unwrap();
+        let checks = checks.unwrap();
+        assert_eq!(checks.len(), 2);
+        assert!(checks.iter().all(Check::passed));
+    }
+}
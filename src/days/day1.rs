@@ -0,0 +1,108 @@
+use std::iter::successors;
+use std::ops::Div;
+
+use super::{Error, Solution};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<u32>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(&self, input: &str) -> Result<Vec<u32>, Error> {
+        parse_masses(input)
+    }
+
+    fn part1(&self, masses: &Vec<u32>) -> Result<u32, Error> {
+        Ok(masses.iter().cloned().map(fuel_required).sum())
+    }
+
+    fn part2(&self, masses: &Vec<u32>) -> Result<u32, Error> {
+        Ok(masses.iter().cloned().map(total_fuel_required).sum())
+    }
+}
+
+fn parse_masses(input: &str) -> Result<Vec<u32>, Error> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse::<u32>()
+                .map_err(|_| Error::invalid_input(format!("invalid mass {:?}", line)))
+        })
+        .collect()
+}
+
+fn total_fuel_required(mass: u32) -> u32 {
+    successors(Some(mass), |&fuel_mass| Some(fuel_required(fuel_mass)))
+        .skip(1)
+        .take_while(|&fuel_mass| fuel_mass > 0)
+        .sum()
+}
+
+fn fuel_required(mass: u32) -> u32 {
+    mass.div(3).saturating_sub(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::answers::{Answers, Check};
+    use super::*;
+
+    #[test]
+    fn mass_of_12_needs_2_fuel() {
+        assert_eq!(2, fuel_required(12));
+    }
+
+    #[test]
+    fn mass_of_14_needs_2_fuel() {
+        assert_eq!(2, fuel_required(14));
+    }
+
+    #[test]
+    fn mass_of_1969_needs_654_fuel() {
+        assert_eq!(654, fuel_required(1969));
+    }
+
+    #[test]
+    fn mass_of_100756_needs_33583_fuel() {
+        assert_eq!(33583, fuel_required(100_756));
+    }
+
+    #[test]
+    fn total_fuel_for_mass_14_is_2() {
+        assert_eq!(2, total_fuel_required(14));
+    }
+
+    #[test]
+    fn total_fuel_for_mass_1969_is_966() {
+        assert_eq!(966, total_fuel_required(1969));
+    }
+
+    #[test]
+    fn total_fuel_for_mass_100756_is_50346() {
+        assert_eq!(50346, total_fuel_required(100_756));
+    }
+
+    #[test]
+    fn verifies_example_answers() {
+        let answers = Answers {
+            day: 1,
+            input: Default::default(),
+            part1: Some((2 + 2 + 654 + 33583).to_string()),
+            part2: Some((2 + 2 + 966 + 50346).to_string()),
+        };
+        let checks = answers.check("12\n14\n1969\n100756\n");
+        assert!(checks.iter().all(Check::passed), "{:?}", checks);
+    }
+
+    #[test]
+    fn rejects_invalid_masses() {
+        assert!(matches!(
+            Day1.parse("12\nheavy\n"),
+            Err(Error::InvalidInput { .. })
+        ));
+    }
+}
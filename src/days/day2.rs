@@ -0,0 +1,63 @@
+use crate::intcode::io::parse_program;
+use crate::intcode::search::NounVerbSearch;
+use crate::intcode::symbolic::{self, SymbolicComputer};
+use crate::intcode::Computer;
+
+use super::{Error, Solution};
+
+const TARGET: i64 = 19_690_720;
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(&self, input: &str) -> Result<Vec<i64>, Error> {
+        Ok(parse_program(input)?)
+    }
+
+    fn part1(&self, program: &Vec<i64>) -> Result<i64, Error> {
+        Ok(Computer::execute_with_memory_io(program, 12, 2)?)
+    }
+
+    fn part2(&self, program: &Vec<i64>) -> Result<i64, Error> {
+        let (noun, verb) = NounVerbSearch::new(program)
+            .solve(TARGET)
+            .ok_or_else(|| Error::no_solution(format!("no noun and verb produce {}", TARGET)))?;
+        Ok(100 * noun + verb)
+    }
+}
+
+/// Formula of the output in terms of noun and verb, `None` if the output
+/// depends on the path taken.
+pub fn output_formula(program: &[i64]) -> Result<Option<String>, symbolic::Error> {
+    let mut symbolic = SymbolicComputer::new(program);
+    symbolic.set_symbol(1, "noun");
+    symbolic.set_symbol(2, "verb");
+    let paths = symbolic.run()?;
+    Ok(match paths.as_slice() {
+        [path] => Some(path.get(0).to_string()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_with_alarm_state() {
+        let program = Day2.parse("1,0,0,0,99,0,0,0,0,0,0,0,5").unwrap();
+        assert_eq!(Day2.part1(&program).unwrap(), 7);
+    }
+
+    #[test]
+    fn describes_output_formula() {
+        assert_eq!(
+            output_formula(&[1, 1, 2, 0, 99]).unwrap(),
+            Some("mem[noun] + mem[verb]".to_string())
+        );
+    }
+}
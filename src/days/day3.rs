@@ -0,0 +1,1180 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use super::{Error, Solution};
+use geometry::{ordered_crossings, stretches, Crossing, Point, Segment, Stretch};
+
+pub mod geometry;
+pub mod render;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMovementError {
+    kind: MovementErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MovementErrorKind {
+    InvalidDirection,
+    ParseIntError(ParseIntError),
+    NegativeDistance,
+    /// The movement leads to a point whose distance to the origin does not
+    /// fit into an `i32`.
+    OutOfRange,
+}
+
+impl From<ParseIntError> for ParseMovementError {
+    fn from(err: ParseIntError) -> Self {
+        ParseMovementError {
+            kind: MovementErrorKind::ParseIntError(err),
+        }
+    }
+}
+
+impl From<MovementErrorKind> for ParseMovementError {
+    fn from(kind: MovementErrorKind) -> Self {
+        ParseMovementError { kind }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Movement {
+    Up(i32),
+    Down(i32),
+    Left(i32),
+    Right(i32),
+}
+
+impl FromStr for Movement {
+    type Err = ParseMovementError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let distance = || -> Result<i32, ParseMovementError> {
+            let distance = s[1..].parse()?;
+            if distance < 0 {
+                return Err(MovementErrorKind::NegativeDistance.into());
+            }
+            Ok(distance)
+        };
+        match s.get(..1) {
+            Some("U") => Ok(Movement::Up(distance()?)),
+            Some("D") => Ok(Movement::Down(distance()?)),
+            Some("L") => Ok(Movement::Left(distance()?)),
+            Some("R") => Ok(Movement::Right(distance()?)),
+            _ => Err(MovementErrorKind::InvalidDirection.into()),
+        }
+    }
+}
+
+impl Movement {
+    fn apply(&self, point: Point) -> Result<Point, ParseMovementError> {
+        let Point { x, y } = point;
+        let end = match *self {
+            Movement::Up(distance) => y.checked_add(distance).map(|y| Point { x, y }),
+            Movement::Down(distance) => y.checked_sub(distance).map(|y| Point { x, y }),
+            Movement::Left(distance) => x.checked_sub(distance).map(|x| Point { x, y }),
+            Movement::Right(distance) => x.checked_add(distance).map(|x| Point { x, y }),
+        };
+        end.filter(|end| {
+            end.x
+                .checked_abs()
+                .zip(end.y.checked_abs())
+                .and_then(|(x, y)| x.checked_add(y))
+                .is_some()
+        })
+        .ok_or_else(|| MovementErrorKind::OutOfRange.into())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Wire {
+    segments: Vec<Segment>,
+}
+
+impl Wire {
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    pub fn closest_intersection(&self, other: &Self) -> Option<Point> {
+        self.intersections(other)
+            .closest()
+            .map(|crossing| crossing.point)
+    }
+
+    pub fn intersections(&self, other: &Self) -> Intersections {
+        Intersections::new(self, other)
+    }
+
+    /// Steps to the first visit of `target`.
+    pub fn steps(&self, target: Point) -> Option<usize> {
+        self.segments
+            .iter()
+            .find(|segment| segment.contains(target))
+            .map(|segment| segment.steps_to(target))
+    }
+
+    pub fn minimal_intersection_steps(&self, other: &Self) -> Option<usize> {
+        self.intersections(other)
+            .fewest_steps()
+            .as_ref()
+            .map(Crossing::combined_steps)
+    }
+
+    /// Length of the wire in steps.
+    pub fn length(&self) -> usize {
+        self.segments
+            .last()
+            .map_or(0, |segment| segment.steps + segment.len())
+    }
+
+    pub fn end(&self) -> Point {
+        self.segments
+            .last()
+            .map_or_else(Point::default, |segment| segment.end)
+    }
+
+    /// Points the wire passes more than once, ordered by their first visit.
+    ///
+    /// Where the wire runs along itself, the ends of the shared stretch are
+    /// reported. Compares every pair of segments.
+    pub fn self_intersections(&self) -> Vec<SelfIntersection> {
+        let mut points = HashSet::new();
+        for (index, segment) in self.segments.iter().enumerate() {
+            for other in &self.segments[index + 1..] {
+                points.extend(segment.meeting_points(other));
+            }
+        }
+        let mut intersections: Vec<SelfIntersection> = points
+            .into_iter()
+            .filter_map(|point| {
+                let mut visits: Vec<usize> = self
+                    .segments
+                    .iter()
+                    .filter(|segment| segment.contains(point))
+                    .map(|segment| segment.steps_to(point))
+                    .collect();
+                visits.sort_unstable();
+                visits.dedup();
+                if visits.len() > 1 {
+                    Some(SelfIntersection { point, visits })
+                } else {
+                    None
+                }
+            })
+            .collect();
+        intersections.sort_by_key(|intersection| intersection.visits[0]);
+        intersections
+    }
+
+    /// The wire with its loops removed: walking along it, whenever a point
+    /// is reached that is visited again later, the wire continues from the
+    /// last visit. The result leads from the origin to the same end without
+    /// passing any point twice.
+    pub fn without_loops(&self) -> Wire {
+        let mut shortcuts: Vec<(usize, usize)> = self
+            .self_intersections()
+            .iter()
+            .flat_map(|intersection| {
+                let last = intersection.visits[intersection.visits.len() - 1];
+                intersection.visits.iter().map(move |&visit| (visit, last))
+            })
+            .collect();
+        shortcuts.sort_unstable();
+        let mut kept = Vec::new();
+        let mut position = 0;
+        for (visit, last) in shortcuts {
+            if visit >= position && last > visit {
+                kept.push((position, visit));
+                position = last;
+            }
+        }
+        kept.push((position, self.length()));
+
+        let mut segments = Vec::new();
+        let mut steps = 0;
+        for &(from, to) in &kept {
+            for segment in &self.segments {
+                let start = from.max(segment.steps);
+                let end = to.min(segment.steps + segment.len());
+                if start < end {
+                    segments.push(Segment {
+                        start: segment.point_at(start),
+                        end: segment.point_at(end),
+                        steps,
+                    });
+                    steps += end - start;
+                }
+            }
+        }
+        if segments.is_empty() {
+            let end = self.end();
+            segments.push(Segment {
+                start: end,
+                end,
+                steps,
+            });
+        }
+        Wire { segments }
+    }
+}
+
+/// Points other than the origin where two wires meet, including every point
+/// where they run along each other.
+///
+/// The stretches the wires share are kept and their points are enumerated
+/// lazily in the requested order, see [`geometry::ordered_crossings`].
+#[derive(Debug, Clone)]
+pub struct Intersections {
+    /// Stretches leading away from the origin, so the distance grows along
+    /// each of them.
+    arms: Vec<Stretch>,
+}
+
+impl Intersections {
+    pub fn new(first: &Wire, second: &Wire) -> Self {
+        let arms = stretches(&first.segments, &second.segments)
+            .iter()
+            .flat_map(Stretch::arms)
+            // Both wires start at the origin, which does not count as an
+            // intersection. Only the first arm of a stretch can start there.
+            .filter_map(|arm| {
+                if arm.start == Point::default() {
+                    arm.rest()
+                } else {
+                    Some(arm)
+                }
+            })
+            .collect();
+        Intersections { arms }
+    }
+
+    /// The stretches shared by the wires, without the origin.
+    pub fn stretches(&self) -> &[Stretch] {
+        &self.arms
+    }
+
+    /// Number of intersections, which enumerates all of them.
+    pub fn len(&self) -> usize {
+        self.by_distance().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arms.is_empty()
+    }
+
+    /// Intersections by distance to the origin, ties broken by combined
+    /// steps, then by x and y.
+    pub fn by_distance(&self) -> impl Iterator<Item = Crossing> {
+        ordered_crossings(self.arms.clone(), |crossing| {
+            (
+                crossing.distance(),
+                crossing.combined_steps(),
+                crossing.point.x,
+                crossing.point.y,
+            )
+        })
+    }
+
+    /// Intersections by combined steps, ties broken by distance to the
+    /// origin, then by x and y.
+    pub fn by_steps(&self) -> impl Iterator<Item = Crossing> {
+        // The combined steps change by the same amount with every point of an
+        // arm, so each arm is walked towards more steps. Arms with the same
+        // steps throughout keep leading away from the origin.
+        let arms = self
+            .arms
+            .iter()
+            .map(|arm| {
+                if arm.crossing(arm.start).combined_steps() > arm.crossing(arm.end).combined_steps()
+                {
+                    arm.reversed()
+                } else {
+                    *arm
+                }
+            })
+            .collect();
+        ordered_crossings(arms, |crossing| {
+            (
+                crossing.combined_steps(),
+                crossing.distance(),
+                crossing.point.x,
+                crossing.point.y,
+            )
+        })
+    }
+
+    pub fn closest(&self) -> Option<Crossing> {
+        self.by_distance().next()
+    }
+
+    pub fn fewest_steps(&self) -> Option<Crossing> {
+        self.by_steps().next()
+    }
+}
+
+/// Point a wire passes more than once.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SelfIntersection {
+    pub point: Point,
+    /// Steps to each visit, in ascending order.
+    pub visits: Vec<usize>,
+}
+
+impl SelfIntersection {
+    /// Length saved by going straight from the first to the last visit.
+    pub fn loop_length(&self) -> usize {
+        self.visits[self.visits.len() - 1] - self.visits[0]
+    }
+}
+
+impl FromStr for Wire {
+    type Err = ParseMovementError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut start = Point::default();
+        let mut steps = 0;
+        for movement in s.split(',') {
+            let end = movement.parse::<Movement>()?.apply(start)?;
+            let segment = Segment { start, end, steps };
+            steps += segment.len();
+            segments.push(segment);
+            start = end;
+        }
+        Ok(Wire { segments })
+    }
+}
+
+/// Visit of a wire, numbered from zero in input order, at a junction.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Visit {
+    pub wire: usize,
+    /// Fewest steps to the start and to the end of the junction.
+    pub steps: (usize, usize),
+}
+
+/// Point other than the origin, or straight stretch of points, where the
+/// same two or more wires meet. Overlaps are reported as a whole, so they
+/// take no longer to find than single points.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Junction {
+    /// The point of the junction closest to the origin.
+    pub start: Point,
+    pub end: Point,
+    /// The wires meeting, ordered by wire.
+    pub visits: Vec<Visit>,
+}
+
+impl Junction {
+    pub fn distance(&self) -> i32 {
+        self.start.distance()
+    }
+
+    /// Combined steps of the wires to the start.
+    pub fn combined_steps(&self) -> usize {
+        self.visits.iter().map(|visit| visit.steps.0).sum()
+    }
+}
+
+impl fmt::Display for Junction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let list = |values: Vec<String>| values.join(", ");
+        let steps = |end: bool| {
+            list(
+                self.visits
+                    .iter()
+                    .map(|visit| if end { visit.steps.1 } else { visit.steps.0 }.to_string())
+                    .collect(),
+            )
+        };
+        let wires = list(
+            self.visits
+                .iter()
+                .map(|visit| (visit.wire + 1).to_string())
+                .collect(),
+        );
+        if self.start == self.end {
+            write!(
+                f,
+                "wires {} meet at ({}, {}), distance {}, after {} steps",
+                wires,
+                self.start.x,
+                self.start.y,
+                self.distance(),
+                steps(false)
+            )
+        } else {
+            write!(
+                f,
+                "wires {} meet from ({}, {}) to ({}, {}), distance {}, after {} steps at the start and {} at the end",
+                wires,
+                self.start.x,
+                self.start.y,
+                self.end.x,
+                self.end.y,
+                self.distance(),
+                steps(false),
+                steps(true)
+            )
+        }
+    }
+}
+
+/// Intersections of every pair of `wires`, ordered by pair and then by
+/// distance.
+pub fn pairwise_junctions(wires: &[Wire]) -> Vec<Junction> {
+    let mut junctions = Vec::new();
+    for (first, wire) in wires.iter().enumerate() {
+        for (second, other) in wires.iter().enumerate().skip(first + 1) {
+            junctions.extend(junctions_of(&[(first, wire), (second, other)], 2));
+        }
+    }
+    junctions
+}
+
+/// Points and stretches where at least `min_wires` of `wires` meet, ordered
+/// by distance.
+pub fn junctions(wires: &[Wire], min_wires: usize) -> Vec<Junction> {
+    let wires: Vec<(usize, &Wire)> = wires.iter().enumerate().collect();
+    junctions_of(&wires, min_wires)
+}
+
+/// Horizontal line at some y, or vertical line at some x.
+type Line = (bool, i32);
+
+fn point_on(line: Line, position: i32) -> Point {
+    match line {
+        (true, y) => Point { x: position, y },
+        (false, x) => Point { x, y: position },
+    }
+}
+
+fn position_on(line: Line, point: Point) -> i32 {
+    if line.0 {
+        point.x
+    } else {
+        point.y
+    }
+}
+
+/// Junctions of numbered `wires`.
+///
+/// The stretches shared by pairs of wires are cut at each other's ends and
+/// at the crossings on them, so the same wires meet throughout every piece.
+/// Adjacent pieces of a line with the same wires are joined again, except
+/// across the point of the line closest to the origin.
+fn junctions_of(wires: &[(usize, &Wire)], min_wires: usize) -> Vec<Junction> {
+    let mut ranges: BTreeMap<Line, Vec<(i32, i32)>> = BTreeMap::new();
+    let mut points = Vec::new();
+    for (index, (_, wire)) in wires.iter().enumerate() {
+        for (_, other) in &wires[index + 1..] {
+            for stretch in wire.intersections(other).stretches() {
+                if stretch.start == stretch.end {
+                    points.push(stretch.start);
+                    continue;
+                }
+                let line = if stretch.start.y == stretch.end.y {
+                    (true, stretch.start.y)
+                } else {
+                    (false, stretch.start.x)
+                };
+                let start = position_on(line, stretch.start);
+                let end = position_on(line, stretch.end);
+                ranges
+                    .entry(line)
+                    .or_default()
+                    .push((start.min(end), start.max(end)));
+            }
+        }
+    }
+    points.sort_by_key(|point| (point.x, point.y));
+    points.dedup();
+    let covers = |line: Line, position: i32| {
+        ranges.get(&line).is_some_and(|ranges| {
+            ranges
+                .iter()
+                .any(|&(start, end)| (start..=end).contains(&position))
+        })
+    };
+    let meeting = |point: Point| -> Vec<usize> {
+        wires
+            .iter()
+            .filter(|(_, wire)| wire.steps(point).is_some())
+            .map(|&(number, _)| number)
+            .collect()
+    };
+
+    let mut pieces = Vec::new();
+    for (&line, line_ranges) in &ranges {
+        let mut cuts: Vec<i32> = line_ranges
+            .iter()
+            .flat_map(|&(start, end)| vec![start, end])
+            .collect();
+        cuts.extend(
+            points
+                .iter()
+                .filter(|&&point| point_on(line, position_on(line, point)) == point)
+                .map(|&point| position_on(line, point))
+                .chain(std::iter::once(0))
+                .filter(|&position| covers(line, position)),
+        );
+        cuts.sort_unstable();
+        cuts.dedup();
+        // A point covered by both a horizontal and a vertical stretch belongs
+        // to the horizontal one.
+        let mut line_pieces: Vec<(i32, i32)> = Vec::new();
+        for (index, &cut) in cuts.iter().enumerate() {
+            let point = point_on(line, cut);
+            if line.0 || !covers((true, point.y), point.x) {
+                line_pieces.push((cut, cut));
+            }
+            if let Some(&next) = cuts.get(index + 1) {
+                if next - cut > 1 && covers(line, cut + 1) {
+                    line_pieces.push((cut + 1, next - 1));
+                }
+            }
+        }
+        let mut joined: Vec<(i32, i32, Vec<usize>)> = Vec::new();
+        for (start, end) in line_pieces {
+            let wires = meeting(point_on(line, start));
+            match joined.last_mut() {
+                Some(last)
+                    if last.1 + 1 == start && last.2 == wires && (last.0 < 0) == (start < 0) =>
+                {
+                    last.1 = end
+                }
+                _ => joined.push((start, end, wires)),
+            }
+        }
+        pieces.extend(joined.into_iter().map(|(start, end, _)| {
+            let (start, end) = (point_on(line, start), point_on(line, end));
+            if end.distance() < start.distance() {
+                (end, start)
+            } else {
+                (start, end)
+            }
+        }));
+    }
+    pieces.extend(
+        points
+            .into_iter()
+            .filter(|point| !covers((true, point.y), point.x) && !covers((false, point.x), point.y))
+            .map(|point| (point, point)),
+    );
+
+    let mut junctions: Vec<Junction> = pieces
+        .into_iter()
+        .map(|(start, end)| Junction {
+            start,
+            end,
+            visits: wires
+                .iter()
+                .filter_map(|&(number, wire)| {
+                    Some(Visit {
+                        wire: number,
+                        steps: (wire.steps(start)?, wire.steps(end)?),
+                    })
+                })
+                .collect(),
+        })
+        .filter(|junction| junction.visits.len() >= min_wires)
+        .collect();
+    junctions.sort_by_key(|junction| (junction.distance(), junction.start.x, junction.start.y));
+    junctions
+}
+
+/// Lists the intersections of every pair of wires in `input`, the points
+/// and stretches where at least `min_wires` wires meet and where wires cross
+/// themselves.
+pub fn intersection_report(input: &str, min_wires: usize) -> Result<String, Error> {
+    let wires = parse_wires(input)?;
+    let mut report = format!("Intersections of {} wires:\n", wires.len());
+    for junction in pairwise_junctions(&wires) {
+        report += &format!("  {}\n", junction);
+    }
+    report += &format!("Points where {} or more wires meet:\n", min_wires);
+    for junction in junctions(&wires, min_wires) {
+        report += &format!("  {}\n", junction);
+    }
+    report += "Self-intersections:\n";
+    for (index, wire) in wires.iter().enumerate() {
+        for intersection in wire.self_intersections() {
+            report += &format!(
+                "  wire {} crosses itself at ({}, {}) after {:?} steps, a shortcut saves {}\n",
+                index + 1,
+                intersection.point.x,
+                intersection.point.y,
+                intersection.visits,
+                intersection.loop_length()
+            );
+        }
+        let shortest = wire.without_loops().length();
+        if shortest < wire.length() {
+            report += &format!(
+                "  wire {} without loops is {} instead of {} long\n",
+                index + 1,
+                shortest,
+                wire.length()
+            );
+        }
+    }
+    Ok(report)
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Wire>;
+    type Part1 = i32;
+    type Part2 = usize;
+
+    /// Parses two or more wires, of which the puzzle uses the first two.
+    fn parse(&self, input: &str) -> Result<Vec<Wire>, Error> {
+        parse_wires(input)
+    }
+
+    fn part1(&self, wires: &Vec<Wire>) -> Result<i32, Error> {
+        let intersection = wires[0]
+            .closest_intersection(&wires[1])
+            .ok_or_else(|| Error::no_solution("wires do not intersect"))?;
+        Ok(intersection.distance())
+    }
+
+    fn part2(&self, wires: &Vec<Wire>) -> Result<usize, Error> {
+        wires[0]
+            .minimal_intersection_steps(&wires[1])
+            .ok_or_else(|| Error::no_solution("wires do not intersect"))
+    }
+}
+
+fn parse_wires(input: &str) -> Result<Vec<Wire>, Error> {
+    let wires = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.parse::<Wire>()
+                .map_err(|_| Error::invalid_input(format!("invalid wire {:?}", line)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if wires.len() < 2 {
+        return Err(Error::invalid_input("expected at least two wires"));
+    }
+    Ok(wires)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::answers::{Answers, Check};
+    use super::*;
+    use crate::random::Random;
+    use std::collections::HashMap;
+
+    #[test]
+    fn example_1() {
+        assert_eq!(
+            Wire::from_str("R8,U5,L5,D3")
+                .unwrap()
+                .closest_intersection(&Wire::from_str("U7,R6,D4,L4").unwrap()),
+            Some(Point { x: 3, y: 3 })
+        );
+    }
+
+    const EXAMPLE_2: &str = "R75,D30,R83,U83,L12,D49,R71,U7,L72
+U62,R66,U55,R34,D71,R55,D58,R83";
+
+    const EXAMPLE_3: &str = "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
+U98,R91,D20,R16,D67,R40,U7,R15,U6,R7";
+
+    #[test]
+    fn example_2() {
+        let wires = EXAMPLE_2
+            .lines()
+            .map(Wire::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            wires[0].closest_intersection(&wires[1]).unwrap().distance(),
+            159
+        )
+    }
+
+    #[test]
+    fn example_3() {
+        let wires = EXAMPLE_3
+            .lines()
+            .map(Wire::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            wires[0].closest_intersection(&wires[1]).unwrap().distance(),
+            135
+        )
+    }
+
+    #[test]
+    fn part2_example_1() {
+        let wires = [
+            Wire::from_str("R8,U5,L5,D3").unwrap(),
+            Wire::from_str("U7,R6,D4,L4").unwrap(),
+        ];
+        assert_eq!(Some(30), wires[0].minimal_intersection_steps(&wires[1]));
+    }
+
+    #[test]
+    fn part2_example_2() {
+        let wires = [
+            Wire::from_str("R75,D30,R83,U83,L12,D49,R71,U7,L72").unwrap(),
+            Wire::from_str("U62,R66,U55,R34,D71,R55,D58,R83").unwrap(),
+        ];
+        assert_eq!(Some(610), wires[0].minimal_intersection_steps(&wires[1]));
+    }
+
+    #[test]
+    fn verifies_example_answers() {
+        let answers = |part1: &str, part2: &str| Answers {
+            day: 3,
+            input: Default::default(),
+            part1: Some(part1.to_string()),
+            part2: Some(part2.to_string()),
+        };
+        let checks = answers("159", "610")
+            .check(EXAMPLE_2)
+            .into_iter()
+            .chain(answers("135", "410").check(EXAMPLE_3))
+            .collect::<Vec<_>>();
+        assert!(checks.iter().all(Check::passed), "{:?}", checks);
+    }
+
+    #[test]
+    fn requires_two_wires() {
+        assert!(matches!(
+            Day3.parse("R8,U5\n"),
+            Err(Error::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            Day3.parse("R8\nX5\n"),
+            Err(Error::InvalidInput { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_movements_out_of_range() {
+        let error = |kind| Err(ParseMovementError { kind });
+        assert_eq!(
+            "U4294967295".parse::<Wire>(),
+            error(MovementErrorKind::ParseIntError(
+                "4294967295".parse::<i32>().unwrap_err()
+            ))
+        );
+        assert_eq!(
+            "R-5".parse::<Wire>(),
+            error(MovementErrorKind::NegativeDistance)
+        );
+        assert_eq!(
+            "U2147483647,U1".parse::<Wire>(),
+            error(MovementErrorKind::OutOfRange)
+        );
+        assert_eq!(
+            "L2147483647,D1".parse::<Wire>(),
+            error(MovementErrorKind::OutOfRange)
+        );
+        assert!("L2147483647,R2147483647,U2147483647"
+            .parse::<Wire>()
+            .is_ok());
+    }
+
+    const THREE_WIRES: &str = "R8,U5,L5,D3
+U7,R6,D4,L4
+U3,R10";
+
+    #[test]
+    fn lists_pairwise_junctions() {
+        let wires = parse_wires(THREE_WIRES).unwrap();
+        let found: Vec<(usize, usize, Point, Point)> = pairwise_junctions(&wires)
+            .iter()
+            .map(|junction| {
+                (
+                    junction.visits[0].wire,
+                    junction.visits[1].wire,
+                    junction.start,
+                    junction.end,
+                )
+            })
+            .collect();
+        let point = |x, y| Point { x, y };
+        assert_eq!(
+            found,
+            vec![
+                (0, 1, point(3, 3), point(3, 3)),
+                (0, 1, point(6, 5), point(6, 5)),
+                (0, 2, point(3, 3), point(3, 3)),
+                (0, 2, point(8, 3), point(8, 3)),
+                (1, 2, point(0, 1), point(0, 3)),
+                (1, 2, point(2, 3), point(6, 3)),
+            ]
+        );
+        assert_eq!(
+            pairwise_junctions(&wires)[4].to_string(),
+            "wires 2, 3 meet from (0, 1) to (0, 3), distance 1, after 1, 1 steps at the start and 3, 3 at the end"
+        );
+    }
+
+    #[test]
+    fn finds_points_where_many_wires_meet() {
+        let wires = parse_wires(THREE_WIRES).unwrap();
+        assert_eq!(
+            junctions(&wires, 3),
+            vec![Junction {
+                start: Point { x: 3, y: 3 },
+                end: Point { x: 3, y: 3 },
+                visits: vec![
+                    Visit {
+                        wire: 0,
+                        steps: (20, 20)
+                    },
+                    Visit {
+                        wire: 1,
+                        steps: (20, 20)
+                    },
+                    Visit {
+                        wire: 2,
+                        steps: (6, 6)
+                    },
+                ],
+            }]
+        );
+        let found: Vec<(Point, Point)> = junctions(&wires, 2)
+            .iter()
+            .map(|junction| (junction.start, junction.end))
+            .collect();
+        let point = |x, y| Point { x, y };
+        assert_eq!(
+            found,
+            vec![
+                (point(0, 1), point(0, 3)),
+                (point(2, 3), point(2, 3)),
+                (point(3, 3), point(3, 3)),
+                (point(4, 3), point(6, 3)),
+                (point(6, 5), point(6, 5)),
+                (point(8, 3), point(8, 3)),
+            ]
+        );
+        assert_eq!(
+            junctions(&wires, 3)[0].to_string(),
+            "wires 1, 2, 3 meet at (3, 3), distance 6, after 20, 20, 6 steps"
+        );
+    }
+
+    #[test]
+    fn reports_long_overlaps_as_one_junction() {
+        let wires = parse_wires(
+            "R2000000000,U5
+U1,R2000000000,D1
+R1000000000,U1,L1000000000
+R500000000,U1",
+        )
+        .unwrap();
+        let found = |min_wires| -> Vec<(Point, Point, usize)> {
+            junctions(&wires, min_wires)
+                .iter()
+                .map(|junction| (junction.start, junction.end, junction.visits.len()))
+                .collect()
+        };
+        let point = |x, y| Point { x, y };
+        assert_eq!(
+            found(3),
+            vec![
+                (point(1, 0), point(500_000_000, 0), 3),
+                (point(500_000_000, 1), point(500_000_000, 1), 3),
+            ]
+        );
+        assert_eq!(
+            found(2),
+            vec![
+                (point(0, 1), point(499_999_999, 1), 2),
+                (point(1, 0), point(500_000_000, 0), 3),
+                (point(500_000_000, 1), point(500_000_000, 1), 3),
+                (point(500_000_001, 0), point(1_000_000_000, 0), 2),
+                (point(500_000_001, 1), point(1_000_000_000, 1), 2),
+                (point(2_000_000_000, 0), point(2_000_000_000, 1), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_self_intersections() {
+        let wire = Wire::from_str(EXAMPLE_2.lines().next().unwrap()).unwrap();
+        let found: Vec<(i32, i32, Vec<usize>)> = wire
+            .self_intersections()
+            .into_iter()
+            .map(|SelfIntersection { point, visits }| (point.x, point.y, visits))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (158, 4, vec![222, 344]),
+                (158, 11, vec![229, 469]),
+                (146, 11, vec![325, 481]),
+            ]
+        );
+        assert!(Wire::from_str("R8,U5,L5,D3")
+            .unwrap()
+            .self_intersections()
+            .is_empty());
+    }
+
+    #[test]
+    fn removes_loops() {
+        for (line, length, shortened) in [
+            (EXAMPLE_2.lines().next().unwrap(), 482, 360),
+            (EXAMPLE_3.lines().next().unwrap(), 573, 467),
+            ("U7,R6,D4,L4", 21, 21),
+        ]
+        .iter()
+        {
+            let wire = Wire::from_str(line).unwrap();
+            let shortest = wire.without_loops();
+            assert_eq!((wire.length(), shortest.length()), (*length, *shortened));
+            assert_eq!(shortest.end(), wire.end());
+            assert!(shortest.self_intersections().is_empty());
+        }
+        let loop_length = Wire::from_str(EXAMPLE_3.lines().next().unwrap())
+            .unwrap()
+            .self_intersections()[0]
+            .loop_length();
+        assert_eq!(loop_length, 573 - 467);
+    }
+
+    #[test]
+    fn removes_retraced_stretches() {
+        let wire = Wire::from_str("R5,L3,U2,D2,L2").unwrap();
+        assert_eq!(wire.without_loops().length(), 0);
+        assert_eq!(wire.without_loops().end(), Point::default());
+        let wire = Wire::from_str("R5,L3,U2").unwrap();
+        assert_eq!(
+            wire.without_loops().segments(),
+            &[
+                Segment {
+                    start: Point { x: 0, y: 0 },
+                    end: Point { x: 2, y: 0 },
+                    steps: 0
+                },
+                Segment {
+                    start: Point { x: 2, y: 0 },
+                    end: Point { x: 2, y: 2 },
+                    steps: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn excludes_origin_from_intersections() {
+        let first = Wire::from_str("R5").unwrap();
+        let second = Wire::from_str("R3,U2").unwrap();
+        let intersections = first.intersections(&second);
+        assert_eq!(
+            intersections.closest(),
+            Some(Crossing {
+                point: Point { x: 1, y: 0 },
+                steps: (1, 1)
+            })
+        );
+        assert_eq!(first.minimal_intersection_steps(&second), Some(2));
+        assert!(intersections
+            .by_distance()
+            .all(|crossing| crossing.point != Point::default()));
+
+        let away = Wire::from_str("U5").unwrap();
+        assert!(first.intersections(&away).is_empty());
+        assert_eq!(first.closest_intersection(&away), None);
+        assert_eq!(first.minimal_intersection_steps(&away), None);
+    }
+
+    #[test]
+    fn enumerates_long_overlaps_lazily() {
+        let first = Wire::from_str("L1000000000,R2000000000").unwrap();
+        let second = Wire::from_str("R1000000000,L2000000000").unwrap();
+        let intersections = first.intersections(&second);
+        // Each wire reaches the other side of the origin after going out and
+        // back again.
+        let back = 2_000_000_000;
+        let points = |crossings: Vec<Crossing>| -> Vec<(i32, usize)> {
+            crossings
+                .iter()
+                .map(|crossing| (crossing.point.x, crossing.combined_steps()))
+                .collect()
+        };
+        assert_eq!(
+            points(intersections.by_distance().take(3).collect()),
+            [(-1, back + 2), (1, back + 2), (-2, back + 4)]
+        );
+        assert_eq!(
+            points(intersections.by_steps().take(2).collect()),
+            [(-1, back + 2), (1, back + 2)]
+        );
+    }
+
+    #[test]
+    fn resolves_ties_deterministically() {
+        let points = |intersections: &mut dyn Iterator<Item = Crossing>| {
+            intersections
+                .map(|crossing| (crossing.point.x, crossing.point.y))
+                .collect::<Vec<_>>()
+        };
+
+        // Equally distant, but the point above takes fewer steps.
+        let first = Wire::from_str("U2,R4,D4,L2").unwrap();
+        let second = Wire::from_str("R2,U4,D8").unwrap();
+        let intersections = first.intersections(&second);
+        assert_eq!(points(&mut intersections.by_distance()), [(2, 2), (2, -2)]);
+        assert_eq!(points(&mut intersections.by_steps()), [(2, 2), (2, -2)]);
+
+        // All points of the overlap tie on steps, pairs of them on distance.
+        let first = Wire::from_str("R2,U1,L4").unwrap();
+        let second = Wire::from_str("L2,U1,R4").unwrap();
+        let intersections = first.intersections(&second);
+        let expected = [(0, 1), (-1, 1), (1, 1), (-2, 1), (2, 1)];
+        assert_eq!(points(&mut intersections.by_distance()), expected);
+        assert_eq!(points(&mut intersections.by_steps()), expected);
+        assert!(intersections
+            .by_steps()
+            .all(|crossing| crossing.combined_steps() == 10));
+    }
+
+    /// Short wires on a small grid, so they often meet and run along each
+    /// other.
+    fn wire(random: &mut Random) -> String {
+        (0..1 + random.below(6))
+            .map(|_| {
+                format!(
+                    "{}{}",
+                    ["U", "D", "L", "R"][random.below(4) as usize],
+                    random.below(7)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Points of the wire with the steps to their first visit.
+    fn visits(wire: &Wire) -> HashMap<Point, usize> {
+        let mut visits = HashMap::new();
+        for segment in wire.segments() {
+            for steps in segment.steps..=segment.steps + segment.len() {
+                visits.entry(segment.point_at(steps)).or_insert(steps);
+            }
+        }
+        visits
+    }
+
+    #[test]
+    fn intersections_match_brute_force() {
+        let mut random = Random(0x2019_1203);
+        for _ in 0..20_000 {
+            let lines = (wire(&mut random), wire(&mut random));
+            let (first, second) = (
+                Wire::from_str(&lines.0).unwrap(),
+                Wire::from_str(&lines.1).unwrap(),
+            );
+            let (first_visits, second_visits) = (visits(&first), visits(&second));
+            let distance_key = |crossing: &Crossing| {
+                (
+                    crossing.distance(),
+                    crossing.combined_steps(),
+                    crossing.point.x,
+                    crossing.point.y,
+                )
+            };
+            let steps_key = |crossing: &Crossing| {
+                (
+                    crossing.combined_steps(),
+                    crossing.distance(),
+                    crossing.point.x,
+                    crossing.point.y,
+                )
+            };
+            let mut expected: Vec<Crossing> = first_visits
+                .iter()
+                .filter(|(point, _)| **point != Point::default())
+                .filter_map(|(point, steps)| {
+                    second_visits.get(point).map(|other| Crossing {
+                        point: *point,
+                        steps: (*steps, *other),
+                    })
+                })
+                .collect();
+            let intersections = first.intersections(&second);
+            expected.sort_by_key(distance_key);
+            assert_eq!(
+                intersections.by_distance().collect::<Vec<_>>(),
+                expected,
+                "{:?}",
+                lines
+            );
+            expected.sort_by_key(steps_key);
+            assert_eq!(
+                intersections.by_steps().collect::<Vec<_>>(),
+                expected,
+                "{:?}",
+                lines
+            );
+            assert_eq!(intersections.len(), expected.len());
+            assert_eq!(intersections.is_empty(), expected.is_empty());
+        }
+    }
+
+    #[test]
+    fn junctions_match_brute_force() {
+        let mut random = Random(0x2019_0322);
+        for _ in 0..5_000 {
+            let lines: Vec<String> = (0..3).map(|_| wire(&mut random)).collect();
+            let wires = parse_wires(&lines.join("\n")).unwrap();
+            let mut expected: HashMap<Point, Vec<Visit>> = HashMap::new();
+            for (wire, visits) in wires.iter().map(visits).enumerate() {
+                for (point, steps) in visits {
+                    expected.entry(point).or_default().push(Visit {
+                        wire,
+                        steps: (steps, steps),
+                    });
+                }
+            }
+            expected.remove(&Point::default());
+            expected.retain(|_, visits| visits.len() >= 2);
+            let mut found = HashMap::new();
+            for junction in junctions(&wires, 2) {
+                let step = Point {
+                    x: (junction.end.x - junction.start.x).signum(),
+                    y: (junction.end.y - junction.start.y).signum(),
+                };
+                let mut point = junction.start;
+                loop {
+                    // Every wire of the junction meets throughout it.
+                    let visits = junction
+                        .visits
+                        .iter()
+                        .map(|visit| {
+                            let steps = wires[visit.wire].steps(point).unwrap();
+                            Visit {
+                                wire: visit.wire,
+                                steps: (steps, steps),
+                            }
+                        })
+                        .collect();
+                    assert!(found.insert(point, visits).is_none(), "{:?}", lines);
+                    if point == junction.end {
+                        break;
+                    }
+                    point = Point {
+                        x: point.x + step.x,
+                        y: point.y + step.y,
+                    };
+                }
+            }
+            assert_eq!(found, expected, "{:?}", lines);
+        }
+    }
+}
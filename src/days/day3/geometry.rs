@@ -0,0 +1,528 @@
+//! Wires as horizontal and vertical segments on the grid.
+//!
+//! Two wires meet along [`Stretch`]es shared by a segment of each wire. The
+//! stretches are found with a sweep line over the x axis for crossing
+//! segments and by grouping segments on the same line for overlaps, so the
+//! work depends on the number of segments and stretches rather than on the
+//! length of the wires. The points of the stretches are only enumerated by
+//! [`ordered_crossings`].
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+
+#[derive(Eq, PartialEq, Hash, Default, Copy, Clone, Debug)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    /// Manhattan distance to the origin.
+    pub fn distance(self) -> i32 {
+        self.x.abs() + self.y.abs()
+    }
+
+    fn distance_to(self, other: Point) -> usize {
+        ((self.x - other.x).abs() + (self.y - other.y).abs()) as usize
+    }
+}
+
+/// Straight piece of a wire from `start` to `end`, reached after `steps`.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+    pub steps: usize,
+}
+
+impl Segment {
+    /// Whether the segment runs along the x axis, which includes segments of
+    /// a single point.
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    pub fn len(&self) -> usize {
+        self.start.distance_to(self.end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        let within =
+            |value, start, end| (start..=end).contains(&value) || (end..=start).contains(&value);
+        within(point.x, self.start.x, self.end.x) && within(point.y, self.start.y, self.end.y)
+    }
+
+    /// Steps along the wire to `point`, which must lie on the segment.
+    pub fn steps_to(&self, point: Point) -> usize {
+        self.steps + self.start.distance_to(point)
+    }
+
+    /// Point reached after `steps` along the wire, which must lie on the
+    /// segment.
+    pub fn point_at(&self, steps: usize) -> Point {
+        let offset = (steps - self.steps) as i32;
+        Point {
+            x: self.start.x + (self.end.x - self.start.x).signum() * offset,
+            y: self.start.y + (self.end.y - self.start.y).signum() * offset,
+        }
+    }
+
+    /// Points shared with `other`. Of segments running along each other, the
+    /// ends of the shared stretch are returned.
+    pub fn meeting_points(&self, other: &Segment) -> Vec<Point> {
+        if self.is_horizontal() == other.is_horizontal() {
+            let ((start, end), (other_start, other_end)) = (self.span(), other.span());
+            let (start, end) = (start.max(other_start), end.min(other_end));
+            if self.line() != other.line() || start > end {
+                vec![]
+            } else if start == end {
+                vec![self.on_line(start)]
+            } else {
+                vec![self.on_line(start), self.on_line(end)]
+            }
+        } else {
+            let (horizontal, vertical) = if self.is_horizontal() {
+                (self, other)
+            } else {
+                (other, self)
+            };
+            let point = horizontal.on_line(vertical.start.x);
+            if horizontal.contains(point) && vertical.contains(point) {
+                vec![point]
+            } else {
+                vec![]
+            }
+        }
+    }
+
+    /// Range covered along the axis the segment runs on.
+    fn span(&self) -> (i32, i32) {
+        if self.is_horizontal() {
+            (self.start.x.min(self.end.x), self.start.x.max(self.end.x))
+        } else {
+            (self.start.y.min(self.end.y), self.start.y.max(self.end.y))
+        }
+    }
+
+    /// Coordinate of the line the segment runs on.
+    fn line(&self) -> i32 {
+        if self.is_horizontal() {
+            self.start.y
+        } else {
+            self.start.x
+        }
+    }
+
+    fn on_line(&self, position: i32) -> Point {
+        if self.is_horizontal() {
+            Point {
+                x: position,
+                y: self.start.y,
+            }
+        } else {
+            Point {
+                x: self.start.x,
+                y: position,
+            }
+        }
+    }
+}
+
+/// Point where two wires meet, with the fewest steps each wire needs to get
+/// there.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Crossing {
+    pub point: Point,
+    pub steps: (usize, usize),
+}
+
+impl Crossing {
+    pub fn distance(&self) -> i32 {
+        self.point.distance()
+    }
+
+    pub fn combined_steps(&self) -> usize {
+        self.steps.0 + self.steps.1
+    }
+}
+
+/// Straight stretch of points from `start` to `end` that a segment of each
+/// wire passes, a single point where the segments cross.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct Stretch {
+    pub start: Point,
+    pub end: Point,
+    /// The segments of the first and the second wire.
+    pub segments: (Segment, Segment),
+}
+
+impl Stretch {
+    /// Crossing at `point`, which must lie on the stretch, with the steps
+    /// along the two segments.
+    pub fn crossing(&self, point: Point) -> Crossing {
+        Crossing {
+            point,
+            steps: (
+                self.segments.0.steps_to(point),
+                self.segments.1.steps_to(point),
+            ),
+        }
+    }
+
+    /// The stretch without its start, `None` if that is its only point.
+    pub fn rest(&self) -> Option<Stretch> {
+        if self.start == self.end {
+            return None;
+        }
+        let start = Point {
+            x: self.start.x + (self.end.x - self.start.x).signum(),
+            y: self.start.y + (self.end.y - self.start.y).signum(),
+        };
+        Some(Stretch { start, ..*self })
+    }
+
+    pub fn reversed(&self) -> Stretch {
+        Stretch {
+            start: self.end,
+            end: self.start,
+            ..*self
+        }
+    }
+
+    /// Point of the stretch closest to the origin.
+    pub fn closest(&self) -> Point {
+        let clamp = |start: i32, end: i32| 0.max(start.min(end)).min(start.max(end));
+        Point {
+            x: clamp(self.start.x, self.end.x),
+            y: clamp(self.start.y, self.end.y),
+        }
+    }
+
+    /// The stretch split at its point closest to the origin into one or two
+    /// stretches leading away from the origin, the first starting at that
+    /// point.
+    pub fn arms(&self) -> Vec<Stretch> {
+        let closest = self.closest();
+        let mut arms = vec![Stretch {
+            start: closest,
+            ..*self
+        }];
+        arms.extend(
+            Stretch {
+                start: closest,
+                end: self.start,
+                ..*self
+            }
+            .rest(),
+        );
+        arms
+    }
+}
+
+/// Finds the stretches shared by the wires made of the `first` and `second`
+/// segments, in no particular order. A point the wires pass more than once
+/// lies on a stretch for every pair of segments through it.
+pub fn stretches(first: &[Segment], second: &[Segment]) -> Vec<Stretch> {
+    let (first_horizontal, first_vertical) = split(first);
+    let (second_horizontal, second_vertical) = split(second);
+    let crossing = |horizontal: &Segment, vertical: &Segment, segments| {
+        let point = horizontal.on_line(vertical.start.x);
+        Stretch {
+            start: point,
+            end: point,
+            segments,
+        }
+    };
+    let mut stretches = Vec::new();
+    for (horizontal, vertical) in sweep(&first_horizontal, &second_vertical) {
+        stretches.push(crossing(horizontal, vertical, (*horizontal, *vertical)));
+    }
+    for (horizontal, vertical) in sweep(&second_horizontal, &first_vertical) {
+        stretches.push(crossing(horizontal, vertical, (*vertical, *horizontal)));
+    }
+    stretches.extend(overlaps(&first_horizontal, &second_horizontal));
+    stretches.extend(overlaps(&first_vertical, &second_vertical));
+    stretches
+}
+
+/// Crossings at the points of `stretches` ordered by `key`, which must not
+/// decrease from the start to the end of every stretch.
+///
+/// Each point is reported once, with the fewest steps of both wires, as long
+/// as `key` orders the crossings at the same point by their combined steps:
+/// the first segments of both wires to reach a point share a stretch, so the
+/// crossing with the fewest combined steps has the fewest steps of each wire.
+pub fn ordered_crossings<K, F>(stretches: Vec<Stretch>, key: F) -> OrderedCrossings<K, F>
+where
+    K: Ord,
+    F: Fn(&Crossing) -> K,
+{
+    let queue = stretches
+        .iter()
+        .enumerate()
+        .map(|(index, stretch)| Reverse((key(&stretch.crossing(stretch.start)), index)))
+        .collect();
+    OrderedCrossings {
+        stretches,
+        queue,
+        reported: HashSet::new(),
+        key,
+    }
+}
+
+/// Iterator returned by [`ordered_crossings`], merging the stretches lazily.
+pub struct OrderedCrossings<K, F> {
+    /// The points of each stretch that are not yet enumerated.
+    stretches: Vec<Stretch>,
+    /// Key of the next point of each stretch with points left.
+    queue: BinaryHeap<Reverse<(K, usize)>>,
+    reported: HashSet<Point>,
+    key: F,
+}
+
+impl<K, F> Iterator for OrderedCrossings<K, F>
+where
+    K: Ord,
+    F: Fn(&Crossing) -> K,
+{
+    type Item = Crossing;
+
+    fn next(&mut self) -> Option<Crossing> {
+        loop {
+            let Reverse((_, index)) = self.queue.pop()?;
+            let stretch = self.stretches[index];
+            if let Some(rest) = stretch.rest() {
+                let key = (self.key)(&rest.crossing(rest.start));
+                self.queue.push(Reverse((key, index)));
+                self.stretches[index] = rest;
+            }
+            if self.reported.insert(stretch.start) {
+                return Some(stretch.crossing(stretch.start));
+            }
+        }
+    }
+}
+
+fn split(segments: &[Segment]) -> (Vec<&Segment>, Vec<&Segment>) {
+    segments.iter().partition(|segment| segment.is_horizontal())
+}
+
+/// Pairs of horizontal and vertical segments crossing each other.
+///
+/// Moves a vertical line from left to right, keeping the horizontal segments
+/// it cuts ordered by their y coordinate, and looks up the ones each vertical
+/// segment spans.
+fn sweep<'a>(
+    horizontals: &[&'a Segment],
+    verticals: &[&'a Segment],
+) -> Vec<(&'a Segment, &'a Segment)> {
+    // Segments enter before and leave after the vertical segments at the same
+    // x are checked, as the ends belong to the segments.
+    const ENTER: u8 = 0;
+    const CHECK: u8 = 1;
+    const LEAVE: u8 = 2;
+    let mut events: Vec<(i32, u8, usize)> = Vec::new();
+    for (index, horizontal) in horizontals.iter().enumerate() {
+        let (start, end) = horizontal.span();
+        events.push((start, ENTER, index));
+        events.push((end, LEAVE, index));
+    }
+    for (index, vertical) in verticals.iter().enumerate() {
+        events.push((vertical.start.x, CHECK, index));
+    }
+    events.sort_unstable();
+
+    let mut active: BTreeMap<i32, Vec<usize>> = BTreeMap::new();
+    let mut pairs = Vec::new();
+    for (_, kind, index) in events {
+        match kind {
+            ENTER => active
+                .entry(horizontals[index].start.y)
+                .or_default()
+                .push(index),
+            LEAVE => {
+                let y = horizontals[index].start.y;
+                let indices = active.get_mut(&y).expect("Segment is active");
+                indices.retain(|active| *active != index);
+                if indices.is_empty() {
+                    active.remove(&y);
+                }
+            }
+            _ => {
+                let vertical = verticals[index];
+                let (start, end) = vertical.span();
+                for indices in active.range(start..=end).map(|(_, indices)| indices) {
+                    pairs.extend(
+                        indices
+                            .iter()
+                            .map(|&active| (horizontals[active], vertical)),
+                    );
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Stretches shared by segments on the same line.
+fn overlaps(first: &[&Segment], second: &[&Segment]) -> Vec<Stretch> {
+    let mut lines: HashMap<i32, Vec<&Segment>> = HashMap::new();
+    for segment in second {
+        lines.entry(segment.line()).or_default().push(segment);
+    }
+    let mut found = Vec::new();
+    for first in first {
+        let (first_start, first_end) = first.span();
+        for second in lines.get(&first.line()).into_iter().flatten() {
+            let (second_start, second_end) = second.span();
+            let (start, end) = (first_start.max(second_start), first_end.min(second_end));
+            if start <= end {
+                found.push(Stretch {
+                    start: first.on_line(start),
+                    end: first.on_line(end),
+                    segments: (**first, **second),
+                });
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Segments of a wire through `points`, starting at the first.
+    fn wire(points: &[(i32, i32)]) -> Vec<Segment> {
+        let mut steps = 0;
+        points
+            .windows(2)
+            .map(|pair| {
+                let segment = Segment {
+                    start: Point {
+                        x: pair[0].0,
+                        y: pair[0].1,
+                    },
+                    end: Point {
+                        x: pair[1].0,
+                        y: pair[1].1,
+                    },
+                    steps,
+                };
+                steps += segment.len();
+                segment
+            })
+            .collect()
+    }
+
+    /// Crossings of the wires ordered by point.
+    fn sorted(first: &[Segment], second: &[Segment]) -> Vec<(i32, i32, usize, usize)> {
+        let arms = stretches(first, second)
+            .iter()
+            .flat_map(Stretch::arms)
+            .collect();
+        let mut crossings: Vec<_> = ordered_crossings(arms, |crossing| {
+            (crossing.distance(), crossing.combined_steps())
+        })
+        .map(|Crossing { point, steps }| (point.x, point.y, steps.0, steps.1))
+        .collect();
+        crossings.sort_unstable();
+        crossings
+    }
+
+    #[test]
+    fn finds_perpendicular_crossings() {
+        let first = wire(&[(0, 0), (8, 0), (8, 5), (3, 5), (3, 2)]);
+        let second = wire(&[(0, 0), (0, 7), (6, 7), (6, 3), (2, 3)]);
+        assert_eq!(
+            sorted(&first, &second),
+            vec![(0, 0, 0, 0), (3, 3, 20, 20), (6, 5, 15, 15)]
+        );
+    }
+
+    #[test]
+    fn counts_segment_ends() {
+        let first = wire(&[(0, 0), (4, 0)]);
+        let second = wire(&[(0, 2), (4, 2), (4, -2)]);
+        assert_eq!(sorted(&first, &second), vec![(4, 0, 4, 6)]);
+    }
+
+    #[test]
+    fn reports_every_point_of_overlaps() {
+        let first = wire(&[(-5, 1), (5, 1)]);
+        let second = wire(&[(8, 1), (-2, 1), (-2, 4)]);
+        assert_eq!(
+            sorted(&first, &second),
+            (-2..=5)
+                .map(|x| (x, 1, (x + 5) as usize, (8 - x) as usize))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn splits_stretches_at_closest_point() {
+        let (horizontal, vertical) = (wire(&[(-3, 2), (4, 2)]), wire(&[(1, -1), (1, 3)]));
+        let stretch = Stretch {
+            start: Point { x: 3, y: 2 },
+            end: Point { x: -2, y: 2 },
+            segments: (horizontal[0], vertical[0]),
+        };
+        assert_eq!(stretch.closest(), Point { x: 0, y: 2 });
+        let ends: Vec<_> = stretch
+            .arms()
+            .iter()
+            .map(|arm| (arm.start.x, arm.end.x))
+            .collect();
+        assert_eq!(ends, [(0, -2), (1, 3)]);
+        let point = Stretch {
+            start: Point { x: 1, y: 2 },
+            end: Point { x: 1, y: 2 },
+            ..stretch
+        };
+        assert_eq!(point.arms(), [point]);
+        assert_eq!(point.crossing(point.start).steps, (4, 3));
+    }
+
+    #[test]
+    fn keeps_fewest_steps_of_repeated_visits() {
+        let first = wire(&[(0, 0), (0, 4), (2, 4), (2, 2), (-2, 2)]);
+        let second = wire(&[(0, 2), (0, 2)]);
+        assert_eq!(sorted(&first, &second), vec![(0, 2, 2, 0)]);
+    }
+
+    #[test]
+    fn matches_crossings_of_drawn_points() {
+        let first = wire(&[(0, 0), (6, 0), (6, 6), (2, 6), (2, -3), (4, -3), (4, 3)]);
+        let second = wire(&[(0, 0), (0, 4), (7, 4), (7, 1), (1, 1), (1, -4)]);
+        let points = |segments: &[Segment]| -> HashMap<Point, usize> {
+            let mut points = HashMap::new();
+            for segment in segments {
+                for step in 0..=segment.len() {
+                    let point = Point {
+                        x: segment.start.x
+                            + (segment.end.x - segment.start.x).signum() * step as i32,
+                        y: segment.start.y
+                            + (segment.end.y - segment.start.y).signum() * step as i32,
+                    };
+                    points.entry(point).or_insert(segment.steps + step);
+                }
+            }
+            points
+        };
+        let (first_points, second_points) = (points(&first), points(&second));
+        let mut expected: Vec<_> = first_points
+            .iter()
+            .filter_map(|(point, steps)| {
+                second_points
+                    .get(point)
+                    .map(|other| (point.x, point.y, *steps, *other))
+            })
+            .collect();
+        expected.sort_unstable();
+        assert_eq!(sorted(&first, &second), expected);
+    }
+}
@@ -0,0 +1,254 @@
+//! Drawings of wire layouts.
+//!
+//! The ASCII grid uses the notation of the puzzle: `o` marks the origin, `+`
+//! the turns and `X` the intersections. Large layouts are scaled down, so
+//! every cell covers the same number of grid points in both directions.
+//! Extents are computed in `i64`, as a layout may span more than `i32::MAX`.
+
+use super::geometry::{Point, Segment};
+use super::Wire;
+
+const COLORS: [&str; 6] = [
+    "#1f77b4", "#2ca02c", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+/// Smallest and largest coordinates of the wires and the origin.
+fn bounds(wires: &[Wire]) -> (Point, Point) {
+    let mut min = Point::default();
+    let mut max = Point::default();
+    for segment in wires.iter().flat_map(|wire| wire.segments()) {
+        for point in [segment.start, segment.end].iter() {
+            min.x = min.x.min(point.x);
+            min.y = min.y.min(point.y);
+            max.x = max.x.max(point.x);
+            max.y = max.y.max(point.y);
+        }
+    }
+    (min, max)
+}
+
+/// Distance from `start` to `end`, which may exceed `i32::MAX`.
+fn span(start: i32, end: i32) -> i64 {
+    i64::from(end) - i64::from(start)
+}
+
+/// Grid of at most `width` by `height` cells, including a margin of one
+/// cell around the wires.
+struct Grid {
+    cells: Vec<Vec<char>>,
+    min: Point,
+    max: Point,
+    scale: i64,
+}
+
+impl Grid {
+    fn new(wires: &[Wire], width: usize, height: usize) -> Self {
+        let (min, max) = bounds(wires);
+        let cells_for = |span: i64, cells: usize| {
+            let cells = (cells.max(3) - 2) as i64;
+            (span + cells - 1) / cells
+        };
+        let scale = cells_for(span(min.x, max.x) + 1, width)
+            .max(cells_for(span(min.y, max.y) + 1, height))
+            .max(1);
+        let columns = (span(min.x, max.x) / scale + 3) as usize;
+        let rows = (span(min.y, max.y) / scale + 3) as usize;
+        Grid {
+            cells: vec![vec!['.'; columns]; rows],
+            min,
+            max,
+            scale,
+        }
+    }
+
+    /// Row and column of the cell covering `point`, rows counting down from
+    /// the largest y.
+    fn cell(&self, point: Point) -> (usize, usize) {
+        (
+            (span(point.y, self.max.y) / self.scale + 1) as usize,
+            (span(self.min.x, point.x) / self.scale + 1) as usize,
+        )
+    }
+
+    fn set(&mut self, point: Point, symbol: char) {
+        let (row, column) = self.cell(point);
+        self.cells[row][column] = symbol;
+    }
+
+    fn draw(&mut self, segment: &Segment) {
+        let symbol = if segment.is_horizontal() { '-' } else { '|' };
+        self.fill(segment.start, segment.end, symbol);
+    }
+
+    /// Sets the cells covering the straight line from `start` to `end`.
+    fn fill(&mut self, start: Point, end: Point, symbol: char) {
+        let (start_row, start_column) = self.cell(start);
+        let (end_row, end_column) = self.cell(end);
+        for row in start_row.min(end_row)..=start_row.max(end_row) {
+            for column in start_column.min(end_column)..=start_column.max(end_column) {
+                self.cells[row][column] = symbol;
+            }
+        }
+    }
+}
+
+/// Draws `wires` on a grid of at most `width` by `height` characters.
+pub fn render_ascii(wires: &[Wire], width: usize, height: usize) -> String {
+    let mut grid = Grid::new(wires, width, height);
+    for wire in wires {
+        for segment in wire.segments() {
+            grid.draw(segment);
+        }
+    }
+    for wire in wires {
+        let segments: Vec<&Segment> = wire
+            .segments()
+            .iter()
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        for pair in segments.windows(2) {
+            let direction = |segment: &Segment| {
+                (
+                    (segment.end.x - segment.start.x).signum(),
+                    (segment.end.y - segment.start.y).signum(),
+                )
+            };
+            if direction(pair[0]) != direction(pair[1]) {
+                grid.set(pair[0].end, '+');
+            }
+        }
+    }
+    // Overlaps are marked cell by cell rather than point by point, so long
+    // ones take no longer to draw than short ones.
+    for (index, wire) in wires.iter().enumerate() {
+        for other in &wires[index + 1..] {
+            for stretch in wire.intersections(other).stretches() {
+                grid.fill(stretch.start, stretch.end, 'X');
+            }
+        }
+    }
+    grid.set(Point::default(), 'o');
+    grid.cells
+        .iter()
+        .map(|row| row.iter().collect::<String>() + "\n")
+        .collect()
+}
+
+/// Draws `wires` as an SVG image, each wire in its own color, with the
+/// origin as a black dot and the intersection closest to it circled in red.
+pub fn render_svg(wires: &[Wire]) -> String {
+    let (min, max) = bounds(wires);
+    let extent = span(min.x, max.x).max(span(min.y, max.y)).max(1);
+    let stroke = (extent as f64 / 500.0).max(0.1);
+    let margin = stroke * 10.0;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+        min.x as f64 - margin,
+        -max.y as f64 - margin,
+        span(min.x, max.x) as f64 + 2.0 * margin,
+        span(min.y, max.y) as f64 + 2.0 * margin
+    );
+    for (index, wire) in wires.iter().enumerate() {
+        let mut points = vec![Point::default()];
+        points.extend(wire.segments().iter().map(|segment| segment.end));
+        let points: Vec<String> = points
+            .iter()
+            .map(|point| format!("{},{}", point.x, -point.y))
+            .collect();
+        svg += &format!(
+            "  <polyline fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" points=\"{}\"/>\n",
+            COLORS[index % COLORS.len()],
+            stroke,
+            points.join(" ")
+        );
+    }
+    svg += &format!(
+        "  <circle cx=\"0\" cy=\"0\" r=\"{}\" fill=\"black\"/>\n",
+        stroke * 3.0
+    );
+    let closest = wires
+        .iter()
+        .enumerate()
+        .flat_map(|(index, wire)| {
+            wires[index + 1..]
+                .iter()
+                .filter_map(move |other| wire.intersections(other).closest())
+        })
+        .min_by_key(|crossing| crossing.distance());
+    if let Some(closest) = closest {
+        svg += &format!(
+            "  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"red\" stroke-width=\"{}\"/>\n",
+            closest.point.x,
+            -closest.point.y,
+            stroke * 6.0,
+            stroke
+        );
+    }
+    svg + "</svg>\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wires(lines: &str) -> Vec<Wire> {
+        lines.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn draws_example_like_the_puzzle() {
+        assert_eq!(
+            render_ascii(&wires("R8,U5,L5,D3\nU7,R6,D4,L4"), 80, 40),
+            "...........
+.+-----+...
+.|.....|...
+.|..+--X-+.
+.|..|..|.|.
+.|.-X--+.|.
+.|..|....|.
+.|.......|.
+.o-------+.
+...........
+"
+        );
+    }
+
+    #[test]
+    fn scales_large_layouts() {
+        let drawing = render_ascii(&wires("R1000,U500\nU1000,R400,D1000"), 42, 22);
+        let lines: Vec<&str> = drawing.lines().collect();
+        assert!(lines.len() <= 22 && lines.iter().all(|line| line.len() <= 42));
+        assert_eq!(drawing.matches('o').count(), 1);
+        assert_eq!(drawing.matches('X').count(), 1);
+    }
+
+    #[test]
+    fn highlights_closest_intersection() {
+        let svg = render_svg(&wires("R8,U5,L5,D3\nU7,R6,D4,L4"));
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.contains("points=\"0,0 8,0 8,-5 3,-5 3,-2\""));
+        assert!(svg.contains("<circle cx=\"3\" cy=\"-3\""));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+    }
+
+    #[test]
+    fn draws_layouts_wider_than_i32() {
+        let wires = wires("L2000000000\nR2000000000");
+        let drawing = render_ascii(&wires, 42, 22);
+        assert!(drawing.lines().all(|line| line.len() <= 42));
+        assert_eq!(drawing.matches('o').count(), 1);
+        let svg = render_svg(&wires);
+        assert!(svg.contains("viewBox=\"-2080000000 -80000000 4160000000 160000000\""));
+    }
+
+    #[test]
+    fn marks_long_overlaps_by_cell() {
+        let wires = wires("R20000000\nR20000000");
+        let drawing = render_ascii(&wires, 42, 22);
+        assert_eq!(
+            drawing.lines().nth(1),
+            Some(".oXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXXX.")
+        );
+        assert!(render_svg(&wires).contains("<circle cx=\"1\" cy=\"0\""));
+    }
+}
@@ -0,0 +1,122 @@
+use std::ops::RangeInclusive;
+
+use super::{Error, Solution};
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = RangeInclusive<u32>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<RangeInclusive<u32>, Error> {
+        parse_range(input)
+    }
+
+    fn part1(&self, range: &RangeInclusive<u32>) -> Result<usize, Error> {
+        Ok(range
+            .clone()
+            .filter(|password| fits_facts(*password))
+            .count())
+    }
+
+    fn part2(&self, range: &RangeInclusive<u32>) -> Result<usize, Error> {
+        Ok(range
+            .clone()
+            .filter(|password| fits_facts(*password) && fits_additional_rule(*password))
+            .count())
+    }
+}
+
+/// Parses the range of six digit passwords written as `start-end`.
+fn parse_range(input: &str) -> Result<RangeInclusive<u32>, Error> {
+    let invalid = || Error::invalid_input(format!("invalid range {:?}", input.trim()));
+    let mut bounds = input.trim().splitn(2, '-').map(|bound| {
+        bound
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|bound| (100_000..1_000_000).contains(bound))
+    });
+    match (bounds.next().flatten(), bounds.next().flatten()) {
+        (Some(start), Some(end)) => Ok(start..=end),
+        _ => Err(invalid()),
+    }
+}
+
+fn fits_facts(candidate: u32) -> bool {
+    let digits: Vec<u32> = to_digits(candidate);
+    digits.windows(2).any(|digits| digits[0] == digits[1])
+        && (0..digits.len() - 1).all(|i| digits[i] <= digits[i + 1])
+}
+
+fn fits_additional_rule(candidate: u32) -> bool {
+    let digits = to_digits(candidate);
+    let last_index = digits.len() - 1;
+    digits
+        .windows(4)
+        .any(|digits| digits[0] != digits[1] && digits[1] == digits[2] && digits[2] != digits[3])
+        || digits[0] == digits[1] && digits[1] != digits[2]
+        || digits[last_index] == digits[last_index - 1]
+            && digits[last_index - 1] != digits[last_index - 2]
+}
+
+fn to_digits(number: u32) -> Vec<u32> {
+    let mut result = Vec::new();
+    let mut number = number;
+    while number > 10 {
+        result.push(number % 10);
+        number /= 10;
+    }
+    result.push(number);
+    result.reverse();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_111111() {
+        assert!(fits_facts(111_111))
+    }
+
+    #[test]
+    fn rejects_123789() {
+        assert!(!fits_facts(123_789))
+    }
+
+    #[test]
+    fn reject_223450() {
+        assert!(!fits_facts(223_450))
+    }
+
+    #[test]
+    fn digits_of_122345() {
+        assert_eq!(vec![1, 2, 2, 3, 4, 5], to_digits(122_345))
+    }
+
+    #[test]
+    fn additional_criterion_matches_112233() {
+        assert!(fits_additional_rule(112_233))
+    }
+
+    #[test]
+    fn additional_criterion_rejects_123444() {
+        assert!(!fits_additional_rule(123_444))
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_range("172851-675869\n").unwrap(), 172_851..=675_869);
+        assert!(matches!(
+            parse_range("172851"),
+            Err(Error::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            parse_range("1-675869"),
+            Err(Error::InvalidInput { .. })
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+use crate::intcode::io::parse_program;
+use crate::intcode::Computer;
+
+use super::{Error, Solution};
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(&self, input: &str) -> Result<Vec<i64>, Error> {
+        Ok(parse_program(input)?)
+    }
+
+    fn part1(&self, program: &Vec<i64>) -> Result<i64, Error> {
+        diagnostic_code(program, 1)
+    }
+
+    fn part2(&self, program: &Vec<i64>) -> Result<i64, Error> {
+        diagnostic_code(program, 5)
+    }
+}
+
+/// Runs the diagnostic program for `system`, which outputs zero for every
+/// passing test followed by the diagnostic code.
+fn diagnostic_code(program: &[i64], system: i64) -> Result<i64, Error> {
+    let output = Computer::execute(program, &[system])?;
+    let (code, tests) = output
+        .split_last()
+        .ok_or_else(|| Error::no_solution("no diagnostic code"))?;
+    if tests.iter().any(|test| *test != 0) {
+        return Err(Error::no_solution(format!("failed tests {:?}", tests)));
+    }
+    Ok(*code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outputs_diagnostic_code() {
+        let program = Day5.parse("3,0,104,0,4,0,99").unwrap();
+        assert_eq!(Day5.part1(&program).unwrap(), 1);
+        assert_eq!(Day5.part2(&program).unwrap(), 5);
+    }
+
+    #[test]
+    fn reports_failed_tests() {
+        assert!(matches!(
+            diagnostic_code(&[104, 3, 104, 0, 99], 1),
+            Err(Error::NoSolution { .. })
+        ));
+    }
+}
@@ -1,32 +1,44 @@
 use std::collections::{BinaryHeap, HashMap};
 use std::convert::TryFrom;
-use std::io::{stdin, BufRead};
 use std::iter::FromIterator;
 
-fn main() {
-    let orbits = read_orbits();
-    println!("Checksum: {}", orbits.checksum());
-    let source = orbits.center("YOU").expect("YOU not on map");
-    let destination = orbits.center("SAN").expect("SAN not on map");
-    println!(
-        "Required transfers: {}",
-        orbits.distance(source, destination).expect("No path")
-    );
-}
+use super::{Error, Solution};
 
-fn read_orbits() -> OrbitMap {
-    stdin()
-        .lock()
-        .lines()
-        .map(|line| line.expect("Error reading line"))
-        .map(Orbit::try_from)
-        .collect::<Result<OrbitMap, _>>()
-        .expect("Error reading map")
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = OrbitMap;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(&self, input: &str) -> Result<OrbitMap, Error> {
+        parse_orbits(input)
+    }
+
+    fn part1(&self, orbits: &OrbitMap) -> Result<usize, Error> {
+        Ok(orbits.checksum())
+    }
+
+    fn part2(&self, orbits: &OrbitMap) -> Result<usize, Error> {
+        let source = orbits
+            .center("YOU")
+            .ok_or_else(|| Error::invalid_input("YOU not on map"))?;
+        let destination = orbits
+            .center("SAN")
+            .ok_or_else(|| Error::invalid_input("SAN not on map"))?;
+        orbits
+            .distance(source, destination)
+            .ok_or_else(|| Error::no_solution("no path from YOU to SAN"))
+    }
 }
 
-#[derive(Debug)]
-enum Error {
-    InvalidOrbit { orbit: String },
+fn parse_orbits(input: &str) -> Result<OrbitMap, Error> {
+    input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| Orbit::try_from(line.to_string()))
+        .collect()
 }
 
 #[derive(Debug)]
@@ -46,15 +58,13 @@ impl TryFrom<String> for Orbit {
                 satellite: parts[1].to_string(),
             })
         } else {
-            Err(Error::InvalidOrbit {
-                orbit: value.to_string(),
-            })
+            Err(Error::invalid_input(format!("invalid orbit {:?}", value)))
         }
     }
 }
 
 #[derive(Default)]
-struct OrbitMap {
+pub struct OrbitMap {
     outside: HashMap<String, Vec<String>>,
     inside: HashMap<String, String>,
 }
@@ -69,8 +79,8 @@ impl FromIterator<Orbit> for OrbitMap {
     }
 }
 
-impl<'a> OrbitMap {
-    const EMPTY: &'a [String] = &[];
+impl OrbitMap {
+    const EMPTY: &'static [String] = &[];
 
     fn center(&self, object: &str) -> Option<&String> {
         self.inside.get(object)
@@ -106,13 +116,13 @@ impl<'a> OrbitMap {
             if destination == object {
                 return Some(distance);
             }
-            if distance > *distances.get(object).unwrap_or(&std::usize::MAX) {
+            if distance > *distances.get(object).unwrap_or(&usize::MAX) {
                 continue;
             }
 
             let distance = distance + 1;
             for object in self.neighbours(object) {
-                if distance < *distances.get(object.as_str()).unwrap_or(&std::usize::MAX) {
+                if distance < *distances.get(object.as_str()).unwrap_or(&usize::MAX) {
                     queue.push((distance, object));
                     distances.insert(object, distance);
                 }
@@ -125,7 +135,7 @@ impl<'a> OrbitMap {
         let mut queue: Vec<(usize, &str)> = vec![(0, "COM")];
         let mut checksum = 0;
         while let Some((level, object)) = queue.pop() {
-            let satellites = self.around(&object);
+            let satellites = self.around(object);
             let level = level + 1;
             checksum += level * satellites.len();
             for satellite in satellites {
@@ -138,15 +148,11 @@ impl<'a> OrbitMap {
 
 #[cfg(test)]
 mod tests {
+    use super::super::answers::{Answers, Check};
     use super::*;
 
     fn read_lines(lines: &str) -> OrbitMap {
-        lines
-            .lines()
-            .map(|line| line.to_string())
-            .map(Orbit::try_from)
-            .collect::<Result<OrbitMap, _>>()
-            .unwrap()
+        parse_orbits(lines).unwrap()
     }
 
     #[test]
@@ -168,10 +174,7 @@ K)L
         assert_eq!(orbits.checksum(), 42);
     }
 
-    #[test]
-    fn calculates_distance() {
-        let orbits = read_lines(
-            "COM)B
+    const TRANSFER_EXAMPLE: &str = "COM)B
 B)C
 C)D
 D)E
@@ -183,10 +186,25 @@ E)J
 J)K
 K)L
 K)YOU
-I)SAN",
-        );
+I)SAN";
+
+    #[test]
+    fn calculates_distance() {
+        let orbits = read_lines(TRANSFER_EXAMPLE);
         let source = orbits.center("YOU").unwrap();
         let destination = orbits.center("SAN").unwrap();
         assert_eq!(orbits.distance(source, destination), Some(4));
     }
+
+    #[test]
+    fn verifies_example_answers() {
+        let answers = Answers {
+            day: 6,
+            input: Default::default(),
+            part1: Some("54".to_string()),
+            part2: Some("4".to_string()),
+        };
+        let checks = answers.check(TRANSFER_EXAMPLE);
+        assert!(checks.iter().all(Check::passed), "{:?}", checks);
+    }
 }
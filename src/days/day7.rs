@@ -0,0 +1,323 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use crate::intcode::io::parse_program;
+use crate::intcode::{Computer, Error as IntcodeError, Fault, Status};
+
+use super::{Error, Solution};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    fn parse(&self, input: &str) -> Result<Vec<i64>, Error> {
+        Ok(parse_program(input)?)
+    }
+
+    fn part1(&self, program: &Vec<i64>) -> Result<i64, Error> {
+        max_thruster_output(program, Mode::Serial)
+    }
+
+    fn part2(&self, program: &Vec<i64>) -> Result<i64, Error> {
+        max_thruster_output(program, Mode::Feedback)
+    }
+}
+
+fn max_thruster_output(program: &[i64], mode: Mode) -> Result<i64, Error> {
+    find_max_thruster_output(program, mode)?
+        .ok_or_else(|| Error::no_solution("no phase settings produce an output"))
+}
+
+/// Why the amplifiers produce no thruster signal, amplifiers numbered from
+/// zero.
+#[derive(Debug, Eq, PartialEq)]
+enum AmplifierError {
+    Fault(Fault),
+    /// The amplifier halted without passing on a signal.
+    NoOutput {
+        amplifier: usize,
+    },
+    /// The amplifier halted while the ones after it kept running, so they
+    /// would be fed a stale signal.
+    HaltedEarly {
+        amplifier: usize,
+    },
+}
+
+impl From<Fault> for AmplifierError {
+    fn from(fault: Fault) -> Self {
+        AmplifierError::Fault(fault)
+    }
+}
+
+impl From<AmplifierError> for Error {
+    fn from(error: AmplifierError) -> Self {
+        // The puzzle names the amplifiers A to E.
+        let name = |amplifier: usize| (b'A' + amplifier as u8) as char;
+        match error {
+            AmplifierError::Fault(fault) => fault.into(),
+            AmplifierError::NoOutput { amplifier } => Error::invalid_input(format!(
+                "amplifier {} halted without output",
+                name(amplifier)
+            )),
+            AmplifierError::HaltedEarly { amplifier } => Error::invalid_input(format!(
+                "amplifier {} halted before the last one",
+                name(amplifier)
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Mode {
+    Serial,
+    Feedback,
+}
+
+impl Mode {
+    fn phases(self) -> Range<i64> {
+        match self {
+            Mode::Serial => 0..5,
+            Mode::Feedback => 5..10,
+        }
+    }
+
+    fn compute(self, program: &[i64], phase_settings: &[i64]) -> Result<i64, AmplifierError> {
+        match self {
+            Mode::Serial => compute_thruster_output(program, phase_settings),
+            Mode::Feedback => compute_feedback_output(program, phase_settings),
+        }
+    }
+}
+
+fn compute_thruster_output(program: &[i64], phase_settings: &[i64]) -> Result<i64, AmplifierError> {
+    let mut signal = 0;
+    for (amplifier, phase_setting) in phase_settings.iter().enumerate() {
+        let input = VecDeque::from(vec![*phase_setting, signal]);
+        let mut output = None;
+        Computer::with_io(program, input, |value| output = Some(value)).run()?;
+        signal = output.ok_or(AmplifierError::NoOutput { amplifier })?;
+    }
+    Ok(signal)
+}
+
+fn compute_feedback_output(program: &[i64], phase_settings: &[i64]) -> Result<i64, AmplifierError> {
+    let mut amplifiers: Vec<Computer> = phase_settings
+        .iter()
+        .map(|phase_setting| {
+            let mut amplifier = Computer::new(program);
+            // The amplifiers loop many times through the same instructions.
+            amplifier.set_decode_cache(true);
+            amplifier.push_input(*phase_setting);
+            amplifier
+        })
+        .collect();
+    let last = amplifiers.len() - 1;
+    let mut signal = 0;
+    let mut thrusters = None;
+    // Once an amplifier halts, the ones after it must halt in the same round.
+    let mut halted = None;
+    loop {
+        for (index, amplifier) in amplifiers.iter_mut().enumerate() {
+            amplifier.push_input(signal);
+            match (amplifier.resume()?, halted) {
+                (Status::Output(_), Some(first)) => {
+                    return Err(AmplifierError::HaltedEarly { amplifier: first })
+                }
+                (Status::Output(value), None) => {
+                    signal = value;
+                    if index == last {
+                        thrusters = Some(value);
+                    }
+                }
+                (Status::Halted, _) if index == last => {
+                    return thrusters.ok_or(AmplifierError::NoOutput { amplifier: last })
+                }
+                (Status::Halted, _) => halted = halted.or(Some(index)),
+                (Status::NeedsInput, _) => {
+                    return Err(amplifier.fault(IntcodeError::MissingInput).into())
+                }
+            }
+        }
+    }
+}
+
+/// Fails with the first error, as a program that fails on some phase
+/// settings cannot be trusted on the others.
+fn find_max_thruster_output(program: &[i64], mode: Mode) -> Result<Option<i64>, AmplifierError> {
+    let phases: Vec<i64> = mode.phases().collect();
+    let outputs = permutations(&phases)
+        .iter()
+        .map(|phase_settings| mode.compute(program, phase_settings))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(outputs.into_iter().max())
+}
+
+fn permutations<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return vec![vec![]];
+    }
+    (0..items.len())
+        .flat_map(|index| {
+            let mut rest = items.to_vec();
+            let item = rest.remove(index);
+            permutations(&rest).into_iter().map(move |mut permutation| {
+                permutation.insert(0, item.clone());
+                permutation
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::answers::{check, Check};
+    use super::*;
+
+    const SERIAL_EXAMPLE: [i64; 17] = [
+        3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0,
+    ];
+
+    #[test]
+    fn output_example_digits() {
+        assert_eq!(
+            compute_thruster_output(&SERIAL_EXAMPLE, &[4, 3, 2, 1, 0]),
+            Ok(43210)
+        );
+    }
+
+    #[test]
+    fn example_digits() {
+        assert_eq!(
+            find_max_thruster_output(&SERIAL_EXAMPLE, Mode::Serial),
+            Ok(Some(43210))
+        );
+    }
+    #[test]
+    fn example_count_down() {
+        assert_eq!(
+            find_max_thruster_output(
+                &[
+                    3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23,
+                    23, 4, 23, 99, 0, 0
+                ],
+                Mode::Serial
+            ),
+            Ok(Some(54321))
+        );
+    }
+
+    #[test]
+    fn permutations_are_complete_and_distinct() {
+        let mut permutations = permutations(&[0, 1, 2, 3, 4]);
+        assert_eq!(permutations.len(), 120);
+        permutations.sort();
+        permutations.dedup();
+        assert_eq!(permutations.len(), 120);
+        assert_eq!(permutations[0], vec![0, 1, 2, 3, 4]);
+    }
+
+    const FEEDBACK_EXAMPLE: [i64; 29] = [
+        3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1, 28,
+        1005, 28, 6, 99, 0, 0, 5,
+    ];
+
+    #[test]
+    fn feedback_example_output() {
+        assert_eq!(
+            compute_feedback_output(&FEEDBACK_EXAMPLE, &[9, 8, 7, 6, 5]),
+            Ok(139_629_729)
+        );
+    }
+
+    #[test]
+    fn verifies_example_answers() {
+        let text = |program: &[i64]| {
+            program
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let checks = [
+            (1, &SERIAL_EXAMPLE[..], "43210"),
+            (2, &FEEDBACK_EXAMPLE[..], "139629729"),
+        ]
+        .iter()
+        .map(|&(part, program, expected)| check(7, part, &text(program), expected))
+        .collect::<Vec<_>>();
+        assert!(checks.iter().all(Check::passed), "{:?}", checks);
+    }
+
+    #[test]
+    fn feedback_example_maximum() {
+        assert_eq!(
+            find_max_thruster_output(&FEEDBACK_EXAMPLE, Mode::Feedback),
+            Ok(Some(139_629_729))
+        );
+    }
+
+    #[test]
+    fn fails_if_an_amplifier_halts_without_output() {
+        // Passes the signal on, except for a phase setting of 2.
+        let program = [
+            3, 14, 3, 15, 1008, 14, 2, 16, 1005, 16, 13, 4, 15, 99, 0, 0, 0,
+        ];
+        assert_eq!(compute_thruster_output(&program, &[0, 1, 3, 4]), Ok(0));
+        assert_eq!(
+            compute_thruster_output(&program, &[0, 1, 2, 3, 4]),
+            Err(AmplifierError::NoOutput { amplifier: 2 })
+        );
+        assert_eq!(
+            Day7.part1(&program.to_vec()).unwrap_err().to_string(),
+            "invalid input: amplifier C halted without output"
+        );
+    }
+
+    #[test]
+    fn fails_if_an_amplifier_halts_early_in_feedback_loop() {
+        // Adds one to the signal forever, except for a phase setting of 5,
+        // which halts after reading the first signal.
+        let program = [
+            3, 21, 3, 22, 1008, 21, 5, 23, 1005, 23, 20, 101, 1, 22, 22, 4, 22, 1105, 1, 2, 99, 0,
+            0, 0,
+        ];
+        assert_eq!(
+            compute_feedback_output(&program, &[5, 6, 7, 8, 9]),
+            Err(AmplifierError::HaltedEarly { amplifier: 0 })
+        );
+        assert_eq!(
+            compute_feedback_output(&program, &[6, 7, 8, 5, 9]),
+            Err(AmplifierError::HaltedEarly { amplifier: 3 })
+        );
+        assert_eq!(
+            compute_feedback_output(&program, &[6, 7, 8, 9, 5]),
+            Err(AmplifierError::NoOutput { amplifier: 4 })
+        );
+        assert!(Day7.part2(&program.to_vec()).is_err());
+    }
+
+    #[test]
+    fn fails_if_any_phase_setting_faults() {
+        // Appends the phase setting to the signal as a digit, like the
+        // examples, but jumps to an invalid opcode for a phase setting of 4
+        // and a signal of 0, so only some permutations fault.
+        let program = [
+            3, 30, 3, 31, 1002, 31, 10, 32, 1, 30, 32, 32, 1008, 32, 4, 33, 1005, 33, 23, 4, 32,
+            99, 0, 98, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        assert_eq!(
+            compute_thruster_output(&program, &[0, 1, 2, 3, 4]),
+            Ok(1234)
+        );
+        let error = Day7.part1(&program.to_vec()).unwrap_err();
+        assert!(
+            matches!(error, Error::ExecutionFailed { ref fault } if fault.instruction_pointer == 23),
+            "{}",
+            error
+        );
+    }
+}
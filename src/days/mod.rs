@@ -0,0 +1,157 @@
+//! Solutions of the daily puzzles.
+//!
+//! Every day has a module with a type implementing [`Solution`], which is
+//! registered under its day number in [`SOLVERS`].
+
+use std::fmt;
+
+use crate::intcode::{self, Fault};
+
+pub mod answers;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+
+#[derive(Debug)]
+pub enum Error {
+    UnknownDay { day: u8 },
+    UnknownPart { part: u8 },
+    InvalidInput { message: String },
+    InvalidProgram { error: intcode::io::Error },
+    ExecutionFailed { fault: Fault },
+    NoSolution { message: String },
+}
+
+impl Error {
+    pub(crate) fn invalid_input(message: impl Into<String>) -> Self {
+        Error::InvalidInput {
+            message: message.into(),
+        }
+    }
+
+    pub(crate) fn no_solution(message: impl Into<String>) -> Self {
+        Error::NoSolution {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::UnknownDay { day } => write!(f, "no solution for day {}", day),
+            Error::UnknownPart { part } => write!(f, "no part {}, expected 1 or 2", part),
+            Error::InvalidInput { message } => write!(f, "invalid input: {}", message),
+            Error::InvalidProgram { error } => write!(f, "{}", error),
+            Error::ExecutionFailed { fault } => write!(f, "{}", fault),
+            Error::NoSolution { message } => write!(f, "no solution: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidProgram { error } => Some(error),
+            Error::ExecutionFailed { fault } => Some(fault),
+            _ => None,
+        }
+    }
+}
+
+impl From<intcode::io::Error> for Error {
+    fn from(error: intcode::io::Error) -> Self {
+        Error::InvalidProgram { error }
+    }
+}
+
+impl From<Fault> for Error {
+    fn from(fault: Fault) -> Self {
+        Error::ExecutionFailed { fault }
+    }
+}
+
+/// Solution of the puzzles of a day.
+pub trait Solution {
+    /// Puzzle input shared by both parts.
+    type Input;
+    type Part1: fmt::Display;
+    type Part2: fmt::Display;
+
+    fn parse(&self, input: &str) -> Result<Self::Input, Error>;
+
+    fn part1(&self, input: &Self::Input) -> Result<Self::Part1, Error>;
+
+    fn part2(&self, input: &Self::Input) -> Result<Self::Part2, Error>;
+}
+
+/// A [`Solution`] with its input and answers as text, so solutions of
+/// different days can be registered together.
+pub trait Solver: Sync {
+    fn solve(&self, part: u8, input: &str) -> Result<String, Error>;
+}
+
+impl<S: Solution + Sync> Solver for S {
+    fn solve(&self, part: u8, input: &str) -> Result<String, Error> {
+        match part {
+            1 => Ok(self.part1(&self.parse(input)?)?.to_string()),
+            2 => Ok(self.part2(&self.parse(input)?)?.to_string()),
+            _ => Err(Error::UnknownPart { part }),
+        }
+    }
+}
+
+/// Registered solutions by day.
+pub static SOLVERS: [(u8, &dyn Solver); 7] = [
+    (1, &day1::Day1),
+    (2, &day2::Day2),
+    (3, &day3::Day3),
+    (4, &day4::Day4),
+    (5, &day5::Day5),
+    (6, &day6::Day6),
+    (7, &day7::Day7),
+];
+
+/// Looks up the solution registered for `day`.
+pub fn solver(day: u8) -> Result<&'static dyn Solver, Error> {
+    SOLVERS
+        .iter()
+        .find(|(registered, _)| *registered == day)
+        .map(|(_, solver)| *solver)
+        .ok_or(Error::UnknownDay { day })
+}
+
+/// Solves `part` of `day` for `input`.
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, Error> {
+    solver(day)?.solve(part, input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solves_known_puzzles() {
+        assert_eq!(solve(1, 1, "12\n14\n").unwrap(), "4");
+        assert_eq!(solve(4, 1, "111110-111112").unwrap(), "2");
+    }
+
+    #[test]
+    fn registers_days_in_order() {
+        let days: Vec<u8> = SOLVERS.iter().map(|(day, _)| *day).collect();
+        assert_eq!(days, (1..=7).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rejects_unknown_puzzles() {
+        assert!(matches!(solve(8, 1, ""), Err(Error::UnknownDay { day: 8 })));
+        assert!(matches!(
+            solve(1, 3, ""),
+            Err(Error::UnknownPart { part: 3 })
+        ));
+    }
+}
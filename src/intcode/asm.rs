@@ -0,0 +1,1003 @@
+//! Assembler for a textual Intcode language.
+//!
+//! ```text
+//! limit = 10              ; constants
+//! macro inc cell          ; macros with parameters
+//!     add cell, #1 -> cell
+//! endm
+//!
+//!     in -> [counter]
+//! loop:
+//!     out [counter]
+//!     inc [counter]
+//!     lt [counter], #limit -> [flag]
+//!     jnz [flag], #loop
+//!     hlt
+//! counter:
+//!     db 0
+//! flag:
+//!     db 0
+//! ```
+//!
+//! Operands are immediate (`#3`), positional (`[12]`) or relative to the
+//! relative base (`rel+2`, `rel-1`). The parameter an instruction writes to
+//! follows an arrow. Labels local to a macro start with `@`. The language
+//! is the one produced by the disassembler.
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::decode::{Instruction, Operation, Parameter};
+
+const MAX_MACRO_DEPTH: usize = 64;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ErrorKind,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ErrorKind {
+    UnexpectedCharacter {
+        character: char,
+    },
+    UnexpectedToken {
+        expected: &'static str,
+        found: String,
+    },
+    UnknownMnemonic {
+        mnemonic: String,
+    },
+    OperandCount {
+        mnemonic: &'static str,
+        expected: usize,
+        got: usize,
+    },
+    MissingTarget {
+        mnemonic: &'static str,
+    },
+    UnexpectedTarget {
+        mnemonic: &'static str,
+    },
+    UndefinedSymbol {
+        name: String,
+    },
+    DuplicateSymbol {
+        name: String,
+    },
+    RecursiveDefinition {
+        name: String,
+    },
+    MacroArguments {
+        name: String,
+        expected: usize,
+        got: usize,
+    },
+    UnterminatedMacro {
+        name: String,
+    },
+    UnexpectedEndm,
+    NegativeAddress {
+        address: i64,
+    },
+    OutOfRange,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::UnexpectedCharacter { character } => {
+                write!(f, "unexpected character {:?}", character)
+            }
+            ErrorKind::UnexpectedToken { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            ErrorKind::UnknownMnemonic { mnemonic } => write!(f, "unknown mnemonic {}", mnemonic),
+            ErrorKind::OperandCount {
+                mnemonic,
+                expected,
+                got,
+            } => write!(f, "{} takes {} operand(s), got {}", mnemonic, expected, got),
+            ErrorKind::MissingTarget { mnemonic } => {
+                write!(f, "{} needs a target after ->", mnemonic)
+            }
+            ErrorKind::UnexpectedTarget { mnemonic } => {
+                write!(f, "{} does not write to a target", mnemonic)
+            }
+            ErrorKind::UndefinedSymbol { name } => write!(f, "undefined symbol {}", name),
+            ErrorKind::DuplicateSymbol { name } => write!(f, "symbol {} defined twice", name),
+            ErrorKind::RecursiveDefinition { name } => {
+                write!(f, "{} is defined in terms of itself", name)
+            }
+            ErrorKind::MacroArguments {
+                name,
+                expected,
+                got,
+            } => write!(
+                f,
+                "macro {} takes {} argument(s), got {}",
+                name, expected, got
+            ),
+            ErrorKind::UnterminatedMacro { name } => write!(f, "macro {} lacks endm", name),
+            ErrorKind::UnexpectedEndm => write!(f, "endm outside of a macro"),
+            ErrorKind::NegativeAddress { address } => write!(f, "negative address {}", address),
+            ErrorKind::OutOfRange => write!(f, "value does not fit into a word"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Assembles source text into program words.
+pub fn assemble(source: &str) -> Result<Vec<i64>, Error> {
+    let lines = expand_macros(source)?;
+    let mut symbols: HashMap<String, Symbol> = HashMap::new();
+    let mut statements = Vec::new();
+    let mut address = 0;
+    for line in lines.iter() {
+        let tokens = tokenize(line)?;
+        let mut parser = Parser::new(line, &tokens);
+        while parser.peek_symbol(1) == Some(":") {
+            let (name, column) = parser.identifier("label")?;
+            parser.next();
+            define(
+                &mut symbols,
+                name,
+                Symbol::Label(address),
+                line.number,
+                column,
+            )?;
+        }
+        if parser.peek_symbol(1) == Some("=") {
+            let (name, column) = parser.identifier("constant")?;
+            parser.next();
+            let expression = parser.expression()?;
+            parser.end()?;
+            let constant = Symbol::Constant(expression, line.number);
+            define(&mut symbols, name, constant, line.number, column)?;
+        } else if !parser.at_end() {
+            let statement = parser.statement()?;
+            address += statement.size();
+            statements.push((line.number, statement));
+        }
+    }
+
+    let mut program = Vec::with_capacity(address);
+    for (line, statement) in statements.iter() {
+        let resolver = Resolver {
+            symbols: &symbols,
+            line: *line,
+        };
+        match statement {
+            Statement::Data { values } => {
+                for value in values.iter() {
+                    program.push(resolver.evaluate(value, &mut vec![])?);
+                }
+            }
+            Statement::Instruction {
+                operation,
+                operands,
+            } => {
+                let parameters = operands
+                    .iter()
+                    .map(|operand| resolver.parameter(operand))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let instruction = Instruction {
+                    opcode: operation.opcode,
+                    length: parameters.len() + 1,
+                    parameters,
+                };
+                program.extend(instruction.encode());
+            }
+        }
+    }
+    Ok(program)
+}
+
+fn define(
+    symbols: &mut HashMap<String, Symbol>,
+    name: String,
+    symbol: Symbol,
+    line: usize,
+    column: usize,
+) -> Result<(), Error> {
+    match symbols.entry(name) {
+        Entry::Occupied(entry) => Err(Error {
+            line,
+            column,
+            kind: ErrorKind::DuplicateSymbol {
+                name: entry.key().clone(),
+            },
+        }),
+        Entry::Vacant(entry) => {
+            entry.insert(symbol);
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SourceLine {
+    number: usize,
+    text: String,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum Token {
+    Identifier(String),
+    Number(i128),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Identifier(name) => write!(f, "{}", name),
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Symbol(symbol) => write!(f, "{:?}", symbol),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    column: usize,
+}
+
+const SYMBOLS: [&str; 9] = ["->", "#", "[", "]", ",", ":", "=", "+", "-"];
+
+fn is_identifier_start(character: char) -> bool {
+    character.is_ascii_alphabetic() || character == '_' || character == '@'
+}
+
+fn is_identifier_part(character: char) -> bool {
+    character.is_ascii_alphanumeric() || character == '_' || character == '@'
+}
+
+fn strip_comment(text: &str) -> &str {
+    text.split(';').next().unwrap_or_default()
+}
+
+fn tokenize(line: &SourceLine) -> Result<Vec<Spanned>, Error> {
+    let text = strip_comment(&line.text);
+    let mut tokens = Vec::new();
+    let mut characters = text.char_indices().peekable();
+    while let Some(&(start, character)) = characters.peek() {
+        let column = text[..start].chars().count() + 1;
+        let error = |kind| Error {
+            line: line.number,
+            column,
+            kind,
+        };
+        if character.is_whitespace() {
+            characters.next();
+            continue;
+        }
+        let token = if is_identifier_start(character) {
+            let mut end = start;
+            while let Some(&(index, character)) = characters.peek() {
+                if !is_identifier_part(character) {
+                    break;
+                }
+                end = index + character.len_utf8();
+                characters.next();
+            }
+            Token::Identifier(text[start..end].to_string())
+        } else if character.is_ascii_digit() {
+            let mut end = start;
+            while let Some(&(index, character)) = characters.peek() {
+                if !character.is_ascii_digit() {
+                    break;
+                }
+                end = index + 1;
+                characters.next();
+            }
+            let number = text[start..end]
+                .parse()
+                .map_err(|_| error(ErrorKind::OutOfRange))?;
+            Token::Number(number)
+        } else if let Some(symbol) = SYMBOLS
+            .iter()
+            .find(|symbol| text[start..].starts_with(**symbol))
+        {
+            for _ in 0..symbol.len() {
+                characters.next();
+            }
+            Token::Symbol(symbol)
+        } else {
+            return Err(error(ErrorKind::UnexpectedCharacter { character }));
+        };
+        tokens.push(Spanned { token, column });
+    }
+    Ok(tokens)
+}
+
+struct Macro {
+    parameters: Vec<String>,
+    body: Vec<SourceLine>,
+}
+
+/// Collects macro definitions and replaces their invocations by their bodies.
+fn expand_macros(source: &str) -> Result<Vec<SourceLine>, Error> {
+    let mut macros = HashMap::new();
+    let mut lines = Vec::new();
+    let mut source_lines = source.lines().enumerate().map(|(index, text)| SourceLine {
+        number: index + 1,
+        text: text.to_string(),
+    });
+    let mut expansions = 0;
+    while let Some(line) = source_lines.next() {
+        let tokens = tokenize(&line)?;
+        match tokens.first().map(|spanned| &spanned.token) {
+            Some(Token::Identifier(keyword)) if keyword == "macro" => {
+                let mut parser = Parser::new(&line, &tokens);
+                parser.next();
+                let (name, _) = parser.identifier("macro name")?;
+                let mut parameters = Vec::new();
+                while !parser.at_end() {
+                    if !parameters.is_empty() {
+                        parser.symbol(",")?;
+                    }
+                    parameters.push(parser.identifier("parameter")?.0);
+                }
+                let mut body = Vec::new();
+                loop {
+                    let line = source_lines.next().ok_or_else(|| Error {
+                        line: line.number,
+                        column: 1,
+                        kind: ErrorKind::UnterminatedMacro { name: name.clone() },
+                    })?;
+                    if strip_comment(&line.text).trim() == "endm" {
+                        break;
+                    }
+                    body.push(line);
+                }
+                macros.insert(name, Macro { parameters, body });
+            }
+            Some(Token::Identifier(keyword)) if keyword == "endm" => {
+                return Err(Error {
+                    line: line.number,
+                    column: tokens[0].column,
+                    kind: ErrorKind::UnexpectedEndm,
+                })
+            }
+            _ => expand_line(&macros, line, &mut lines, &mut expansions, 0)?,
+        }
+    }
+    Ok(lines)
+}
+
+fn expand_line(
+    macros: &HashMap<String, Macro>,
+    line: SourceLine,
+    lines: &mut Vec<SourceLine>,
+    expansions: &mut usize,
+    depth: usize,
+) -> Result<(), Error> {
+    let tokens = tokenize(&line)?;
+    let mut position = 0;
+    while matches!(
+        tokens.get(position + 1),
+        Some(Spanned {
+            token: Token::Symbol(":"),
+            ..
+        })
+    ) {
+        position += 2;
+    }
+    let invocation = match tokens.get(position) {
+        Some(Spanned {
+            token: Token::Identifier(name),
+            column,
+        }) => macros
+            .get(name)
+            .map(|definition| (name, *column, definition)),
+        _ => None,
+    };
+    let (name, column, definition) = match invocation {
+        Some(invocation) => invocation,
+        None => {
+            lines.push(line);
+            return Ok(());
+        }
+    };
+    let error = |kind| Error {
+        line: line.number,
+        column,
+        kind,
+    };
+    if depth >= MAX_MACRO_DEPTH {
+        return Err(error(ErrorKind::RecursiveDefinition { name: name.clone() }));
+    }
+    let text = strip_comment(&line.text);
+    let split = text
+        .char_indices()
+        .nth(column - 1)
+        .map(|(index, _)| index)
+        .unwrap_or_default();
+    let arguments_text = text[split + name.len()..].trim();
+    let arguments: Vec<&str> = if arguments_text.is_empty() {
+        vec![]
+    } else {
+        arguments_text.split(',').map(str::trim).collect()
+    };
+    if arguments.len() != definition.parameters.len() {
+        return Err(error(ErrorKind::MacroArguments {
+            name: name.clone(),
+            expected: definition.parameters.len(),
+            got: arguments.len(),
+        }));
+    }
+    if position > 0 {
+        lines.push(SourceLine {
+            number: line.number,
+            text: text[..split].to_string(),
+        });
+    }
+    *expansions += 1;
+    let expansion = *expansions;
+    for body_line in definition.body.iter() {
+        let text = substitute(&body_line.text, |identifier| {
+            if identifier.starts_with('@') {
+                Some(format!("{}_{}", identifier, expansion))
+            } else {
+                definition
+                    .parameters
+                    .iter()
+                    .position(|parameter| parameter == identifier)
+                    .map(|index| arguments[index].to_string())
+            }
+        });
+        let body_line = SourceLine {
+            number: body_line.number,
+            text,
+        };
+        expand_line(macros, body_line, lines, expansions, depth + 1)?;
+    }
+    Ok(())
+}
+
+/// Replaces identifiers in `text` for which `replacement` returns a value.
+fn substitute(text: &str, replacement: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::new();
+    let mut identifier = String::new();
+    for character in text.chars().chain(std::iter::once(' ')) {
+        if is_identifier_part(character)
+            && (!identifier.is_empty() || is_identifier_start(character))
+        {
+            identifier.push(character);
+            continue;
+        }
+        if !identifier.is_empty() {
+            result.push_str(&replacement(&identifier).unwrap_or_else(|| identifier.clone()));
+            identifier.clear();
+        }
+        result.push(character);
+    }
+    result.pop();
+    result
+}
+
+#[derive(Debug, Clone)]
+enum Atom {
+    Number(i128),
+    Symbol(String),
+}
+
+#[derive(Debug, Clone)]
+struct Expression {
+    column: usize,
+    terms: Vec<(bool, Atom)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Mode {
+    Address,
+    Value,
+    Relative,
+}
+
+#[derive(Debug, Clone)]
+struct Operand {
+    mode: Mode,
+    expression: Expression,
+}
+
+#[derive(Debug)]
+enum Statement {
+    Instruction {
+        operation: Operation,
+        operands: Vec<Operand>,
+    },
+    Data {
+        values: Vec<Expression>,
+    },
+}
+
+impl Statement {
+    fn size(&self) -> usize {
+        match self {
+            Statement::Instruction { operands, .. } => 1 + operands.len(),
+            Statement::Data { values } => values.len(),
+        }
+    }
+}
+
+struct Parser<'a> {
+    line: &'a SourceLine,
+    tokens: &'a [Spanned],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(line: &'a SourceLine, tokens: &'a [Spanned]) -> Self {
+        Parser {
+            line,
+            tokens,
+            position: 0,
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position).map(|spanned| &spanned.token)
+    }
+
+    fn peek_symbol(&self, offset: usize) -> Option<&'static str> {
+        match self.tokens.get(self.position + offset) {
+            Some(Spanned {
+                token: Token::Symbol(symbol),
+                ..
+            }) => Some(symbol),
+            _ => None,
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map(|spanned| spanned.column)
+            .unwrap_or_else(|| strip_comment(&self.line.text).trim_end().chars().count() + 1)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn error(&self, kind: ErrorKind) -> Error {
+        Error {
+            line: self.line.number,
+            column: self.column(),
+            kind,
+        }
+    }
+
+    fn unexpected(&self, expected: &'static str) -> Error {
+        let found = self
+            .peek()
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "end of line".to_string());
+        self.error(ErrorKind::UnexpectedToken { expected, found })
+    }
+
+    fn end(&self) -> Result<(), Error> {
+        if self.at_end() {
+            Ok(())
+        } else {
+            Err(self.unexpected("end of line"))
+        }
+    }
+
+    fn symbol(&mut self, symbol: &'static str) -> Result<(), Error> {
+        if self.peek_symbol(0) == Some(symbol) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.unexpected(symbol))
+        }
+    }
+
+    fn identifier(&mut self, expected: &'static str) -> Result<(String, usize), Error> {
+        let column = self.column();
+        match self.peek() {
+            Some(Token::Identifier(name)) => {
+                self.next();
+                Ok((name.clone(), column))
+            }
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement, Error> {
+        let column = self.column();
+        let (mnemonic, _) = self.identifier("mnemonic")?;
+        if mnemonic == "db" {
+            let mut values = vec![self.expression()?];
+            while !self.at_end() {
+                self.symbol(",")?;
+                values.push(self.expression()?);
+            }
+            return Ok(Statement::Data { values });
+        }
+        let operation = Operation::from_mnemonic(&mnemonic).ok_or(Error {
+            line: self.line.number,
+            column,
+            kind: ErrorKind::UnknownMnemonic { mnemonic },
+        })?;
+        let mut operands = Vec::new();
+        while !self.at_end() && self.peek_symbol(0) != Some("->") {
+            if !operands.is_empty() {
+                self.symbol(",")?;
+            }
+            operands.push(self.operand()?);
+        }
+        let target = if self.peek_symbol(0) == Some("->") {
+            self.next();
+            Some(self.operand()?)
+        } else {
+            None
+        };
+        self.end()?;
+        let count_error = |got| Error {
+            line: self.line.number,
+            column,
+            kind: ErrorKind::OperandCount {
+                mnemonic: operation.mnemonic,
+                expected: operation.parameters - operation.writes.iter().count(),
+                got,
+            },
+        };
+        match (operation.writes, target) {
+            (Some(index), Some(target)) if index <= operands.len() => {
+                operands.insert(index, target)
+            }
+            (Some(_), Some(_)) => return Err(count_error(operands.len())),
+            (Some(_), None) => {
+                return Err(Error {
+                    line: self.line.number,
+                    column,
+                    kind: ErrorKind::MissingTarget {
+                        mnemonic: operation.mnemonic,
+                    },
+                })
+            }
+            (None, Some(_)) => {
+                return Err(Error {
+                    line: self.line.number,
+                    column,
+                    kind: ErrorKind::UnexpectedTarget {
+                        mnemonic: operation.mnemonic,
+                    },
+                })
+            }
+            (None, None) => {}
+        }
+        if operands.len() != operation.parameters {
+            return Err(count_error(
+                operands.len() - operation.writes.iter().count(),
+            ));
+        }
+        Ok(Statement::Instruction {
+            operation,
+            operands,
+        })
+    }
+
+    fn operand(&mut self) -> Result<Operand, Error> {
+        match self.peek() {
+            Some(Token::Symbol("#")) => {
+                self.next();
+                let expression = self.expression()?;
+                Ok(Operand {
+                    mode: Mode::Value,
+                    expression,
+                })
+            }
+            Some(Token::Symbol("[")) => {
+                self.next();
+                let expression = self.expression()?;
+                self.symbol("]")?;
+                Ok(Operand {
+                    mode: Mode::Address,
+                    expression,
+                })
+            }
+            Some(Token::Identifier(name)) if name == "rel" => {
+                let column = self.column();
+                self.next();
+                let expression = match self.peek_symbol(0) {
+                    Some("+") => {
+                        self.next();
+                        self.expression()?
+                    }
+                    Some("-") => self.expression()?,
+                    _ => Expression {
+                        column,
+                        terms: vec![(false, Atom::Number(0))],
+                    },
+                };
+                Ok(Operand {
+                    mode: Mode::Relative,
+                    expression,
+                })
+            }
+            _ => Err(self.unexpected("operand")),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression, Error> {
+        let column = self.column();
+        let mut terms = Vec::new();
+        let mut negative = match self.peek_symbol(0) {
+            Some("-") => {
+                self.next();
+                true
+            }
+            Some("+") => {
+                self.next();
+                false
+            }
+            _ => false,
+        };
+        loop {
+            let atom = match self.peek() {
+                Some(Token::Number(number)) => Atom::Number(*number),
+                Some(Token::Identifier(name)) if name != "rel" => Atom::Symbol(name.clone()),
+                _ => return Err(self.unexpected("number or symbol")),
+            };
+            self.next();
+            terms.push((negative, atom));
+            negative = match self.peek_symbol(0) {
+                Some("+") => false,
+                Some("-") => true,
+                _ => return Ok(Expression { column, terms }),
+            };
+            self.next();
+        }
+    }
+}
+
+enum Symbol {
+    Label(usize),
+    Constant(Expression, usize),
+}
+
+struct Resolver<'a> {
+    symbols: &'a HashMap<String, Symbol>,
+    line: usize,
+}
+
+impl<'a> Resolver<'a> {
+    fn error(&self, line: usize, column: usize, kind: ErrorKind) -> Error {
+        Error { line, column, kind }
+    }
+
+    fn evaluate(&self, expression: &Expression, visiting: &mut Vec<String>) -> Result<i64, Error> {
+        self.evaluate_at(expression, self.line, visiting)
+    }
+
+    fn evaluate_at(
+        &self,
+        expression: &Expression,
+        line: usize,
+        visiting: &mut Vec<String>,
+    ) -> Result<i64, Error> {
+        let column = expression.column;
+        let mut sum: i128 = 0;
+        for (negative, atom) in expression.terms.iter() {
+            let value = match atom {
+                Atom::Number(number) => *number,
+                Atom::Symbol(name) => match self.symbols.get(name) {
+                    Some(Symbol::Label(address)) => *address as i128,
+                    Some(Symbol::Constant(expression, definition)) => {
+                        if visiting.contains(name) {
+                            let kind = ErrorKind::RecursiveDefinition { name: name.clone() };
+                            return Err(self.error(*definition, expression.column, kind));
+                        }
+                        visiting.push(name.clone());
+                        let value = self.evaluate_at(expression, *definition, visiting)?;
+                        visiting.pop();
+                        i128::from(value)
+                    }
+                    None => {
+                        let kind = ErrorKind::UndefinedSymbol { name: name.clone() };
+                        return Err(self.error(line, column, kind));
+                    }
+                },
+            };
+            let out_of_range = || self.error(line, column, ErrorKind::OutOfRange);
+            sum = if *negative {
+                sum.checked_sub(value).ok_or_else(out_of_range)?
+            } else {
+                sum.checked_add(value).ok_or_else(out_of_range)?
+            };
+        }
+        i64::try_from(sum).map_err(|_| self.error(line, column, ErrorKind::OutOfRange))
+    }
+
+    fn parameter(&self, operand: &Operand) -> Result<Parameter<i64>, Error> {
+        let value = self.evaluate(&operand.expression, &mut vec![])?;
+        Ok(match operand.mode {
+            Mode::Value => Parameter::Value { value },
+            Mode::Relative => Parameter::Relative { offset: value },
+            Mode::Address => Parameter::Address {
+                address: usize::try_from(value).map_err(|_| {
+                    let kind = ErrorKind::NegativeAddress { address: value };
+                    self.error(self.line, operand.expression.column, kind)
+                })?,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::disasm::disassemble;
+    use crate::intcode::Computer;
+
+    fn error(line: usize, column: usize, kind: ErrorKind) -> Result<Vec<i64>, Error> {
+        Err(Error { line, column, kind })
+    }
+
+    #[test]
+    fn assembles_instructions() {
+        assert_eq!(
+            assemble("add [12], #3 -> [7]\nout rel-2\nin -> rel+1\narb #5\nhlt"),
+            Ok(vec![1001, 12, 3, 7, 204, -2, 203, 1, 109, 5, 99])
+        );
+    }
+
+    #[test]
+    fn resolves_labels_and_constants() {
+        let source = "
+            start = 7
+            answer = start - 1 + end
+                jz #0, #end ; skip data
+            data: db 1, -2, answer
+            end:
+                out [data + 2]
+                hlt";
+        assert_eq!(assemble(source), Ok(vec![1106, 0, 6, 1, -2, 12, 4, 5, 99]));
+    }
+
+    #[test]
+    fn expands_macros() {
+        let source = "
+            macro copy from, to
+                add from, #0 -> to
+            endm
+            macro twice value
+            @here: copy value, [@here]
+                copy value, [@here]
+            endm
+            start: twice #3
+            hlt";
+        assert_eq!(assemble(source), Ok(vec![1101, 3, 0, 0, 1101, 3, 0, 0, 99]));
+    }
+
+    #[test]
+    fn runs_assembled_program() {
+        let source = "
+            limit = 3
+            macro inc cell
+                add cell, #1 -> cell
+            endm
+            loop:
+                out [counter]
+                inc [counter]
+                lt [counter], #limit -> [flag]
+                jnz [flag], #loop
+                hlt
+            counter: db 0
+            flag: db 0";
+        let program = assemble(source).unwrap();
+        assert_eq!(Computer::execute(&program, &[]), Ok(vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn reports_line_and_column() {
+        assert_eq!(
+            assemble("hlt\n  foo #1"),
+            error(
+                2,
+                3,
+                ErrorKind::UnknownMnemonic {
+                    mnemonic: "foo".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            assemble("out #missing"),
+            error(
+                1,
+                6,
+                ErrorKind::UndefinedSymbol {
+                    name: "missing".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            assemble("add #1, #2"),
+            error(1, 1, ErrorKind::MissingTarget { mnemonic: "add" })
+        );
+        assert_eq!(
+            assemble("out #1 $"),
+            error(1, 8, ErrorKind::UnexpectedCharacter { character: '$' })
+        );
+        assert_eq!(
+            assemble("out [1"),
+            error(
+                1,
+                7,
+                ErrorKind::UnexpectedToken {
+                    expected: "]",
+                    found: "end of line".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            assemble("a = b\nb = a\nout #a"),
+            error(
+                1,
+                5,
+                ErrorKind::RecursiveDefinition {
+                    name: "a".to_string()
+                }
+            )
+        );
+        assert_eq!(
+            assemble("x: hlt\nx: hlt"),
+            error(
+                2,
+                1,
+                ErrorKind::DuplicateSymbol {
+                    name: "x".to_string()
+                }
+            )
+        );
+        let max = i128::MAX.to_string();
+        assert_eq!(
+            assemble(&format!("db {} + {}", max, max)),
+            error(1, 4, ErrorKind::OutOfRange)
+        );
+        assert_eq!(
+            assemble(&format!("db -{} - {}", max, max)),
+            error(1, 4, ErrorKind::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn round_trips_with_disassembler() {
+        let programs: Vec<Vec<i64>> = vec![
+            vec![1002, 9, 3, 10, 4, 10, 204, -2, 99, 33, 0, 5],
+            vec![3, 13, 1005, 13, 10, 104, 0, 1105, 1, 12, 104, 1, 99, 0],
+            vec![1105, 1, 2, 0, 0, 0, 99],
+            vec![1, 1, 1, 4, 99, 5, 6, 0, 99],
+            vec![-1, 10099, i64::MIN, i64::MAX],
+            vec![
+                3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36,
+                98, 0, 0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000,
+                1, 20, 4, 20, 1105, 1, 46, 98, 99,
+            ],
+        ];
+        for program in programs {
+            assert_eq!(assemble(&disassemble(&program)), Ok(program));
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use super::decode::{Decoded, MAX_PARAMETERS};
+
+/// Addresses above this are decoded on every execution instead of being cached,
+/// so a program jumping far away cannot make the cache grow without bounds.
+const MAX_CACHED_ADDRESS: usize = 1 << 16;
+
+/// Decoded instructions keyed by their address.
+///
+/// Entries must be invalidated whenever memory they were decoded from changes.
+/// The cache starts disabled, filling it only pays off for instructions that
+/// are executed many times, as in loops.
+#[derive(Debug, Clone)]
+pub struct DecodeCache<W> {
+    entries: Vec<Option<Decoded<W>>>,
+    enabled: bool,
+}
+
+impl<W> Default for DecodeCache<W> {
+    fn default() -> Self {
+        DecodeCache {
+            entries: Vec::new(),
+            enabled: false,
+        }
+    }
+}
+
+impl<W: Clone> DecodeCache<W> {
+    pub fn get(&self, address: usize) -> Option<&Decoded<W>> {
+        self.entries.get(address)?.as_ref()
+    }
+
+    pub fn insert(&mut self, address: usize, decoded: Decoded<W>) {
+        if !self.enabled || address >= MAX_CACHED_ADDRESS {
+            return;
+        }
+        if self.entries.len() <= address {
+            self.entries.resize(address + 1, None);
+        }
+        self.entries[address] = Some(decoded);
+    }
+
+    /// Drops all instructions spanning `address`.
+    pub fn invalidate(&mut self, address: usize) {
+        for start in address.saturating_sub(MAX_PARAMETERS)..=address {
+            if let Some(entry) = self.entries.get_mut(start) {
+                if entry
+                    .as_ref()
+                    .is_some_and(|decoded| start + decoded.length > address)
+                {
+                    *entry = None;
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoded(program: &[i64]) -> Decoded<i64> {
+        Decoded::decode(program).unwrap()
+    }
+
+    #[test]
+    fn invalidates_instructions_spanning_written_address() {
+        let mut cache = DecodeCache::default();
+        cache.set_enabled(true);
+        cache.insert(0, decoded(&[1, 0, 0, 0]));
+        cache.insert(4, decoded(&[4, 0]));
+        cache.insert(6, decoded(&[99]));
+        cache.invalidate(7);
+        assert!(cache.get(0).is_some() && cache.get(4).is_some() && cache.get(6).is_some());
+        cache.invalidate(5);
+        assert!(cache.get(0).is_some() && cache.get(6).is_some());
+        assert!(cache.get(4).is_none());
+        cache.invalidate(3);
+        assert!(cache.get(0).is_none());
+    }
+
+    #[test]
+    fn ignores_inserts_when_disabled() {
+        let mut cache = DecodeCache::default();
+        cache.set_enabled(false);
+        cache.insert(0, decoded(&[99]));
+        assert!(cache.get(0).is_none());
+    }
+}
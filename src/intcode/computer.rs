@@ -1,127 +1,541 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use super::cache::DecodeCache;
 use super::decode;
-use super::decode::{Instruction, Parameter};
+use super::decode::{Decoded, Instruction, Operation, Parameter};
+use super::device::{DeviceError, Input, Output};
+use super::disasm::format_instruction;
+use super::memory::{self, Memory};
+use super::snapshot::{self, Snapshot};
+use super::trace::{Record, Tracer};
+use super::word::Word;
+
+/// Number of executed instructions a fault reports.
+const RECENT_INSTRUCTIONS: usize = 8;
 
 #[derive(Default)]
-pub struct Computer {
-    memory: Vec<i32>,
-    output: Vec<i32>,
-    input: Vec<i32>,
+pub struct Computer<W = i64, I = VecDeque<W>, O = Vec<W>> {
+    memory: Memory<W>,
+    output: O,
+    input: I,
+    instruction_pointer: usize,
+    relative_base: W,
+    tracer: Option<Box<dyn Tracer<W>>>,
+    cache: DecodeCache<W>,
+    steps: usize,
+    recent: VecDeque<usize>,
+}
+
+/// Clones the state and devices of the computer, but not its tracer.
+impl<W: Word, I: Clone, O: Clone> Clone for Computer<W, I, O> {
+    fn clone(&self) -> Self {
+        Computer {
+            memory: self.memory.clone(),
+            output: self.output.clone(),
+            input: self.input.clone(),
+            instruction_pointer: self.instruction_pointer,
+            relative_base: self.relative_base.clone(),
+            tracer: None,
+            cache: self.cache.clone(),
+            steps: self.steps,
+            recent: self.recent.clone(),
+        }
+    }
+}
+
+/// Reason why a resumed computer stopped executing.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum Status<W = i64> {
+    /// The program executed opcode 99 and cannot be resumed any further.
+    Halted,
+    /// An input instruction found no pending input. Push more input and resume.
+    NeedsInput,
+    /// The program produced an output value.
+    Output(W),
 }
 
 #[derive(Eq, PartialEq, Debug)]
-pub enum Error {
+pub enum Error<W = i64> {
     InstructionError { error: decode::Error },
     UnsupportedOperation { opcode: usize },
-    NoAddress { parameter: Parameter },
-    InvalidAddress { address: usize },
+    NoAddress { parameter: Parameter<W> },
+    AddressOutOfRange { address: W },
     ParameterCount { expected: usize, got: usize },
+    Overflow { opcode: usize },
+    MemoryLimit { limit: usize },
+    DeviceError { error: DeviceError },
     MissingInput,
 }
 
-impl Error {
-    fn no_address(parameter: Parameter) -> Self {
+impl<W> Error<W> {
+    fn no_address(parameter: Parameter<W>) -> Self {
         Error::NoAddress { parameter }
     }
 }
 
-impl From<decode::Error> for Error {
+impl<W: fmt::Debug + fmt::Display> fmt::Display for Error<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InstructionError { error } => write!(f, "{}", error),
+            Error::UnsupportedOperation { opcode } => {
+                write!(f, "unsupported operation {}", opcode)
+            }
+            Error::NoAddress { parameter } => write!(f, "parameter {:?} has no address", parameter),
+            Error::AddressOutOfRange { address } => write!(f, "address {} out of range", address),
+            Error::ParameterCount { expected, got } => {
+                write!(f, "expected {} parameter(s), got {}", expected, got)
+            }
+            Error::Overflow { opcode } => write!(f, "overflow in opcode {}", opcode),
+            Error::MemoryLimit { limit } => write!(f, "memory limit of {} cells exceeded", limit),
+            Error::DeviceError { error } => write!(f, "{}", error),
+            Error::MissingInput => write!(f, "missing input"),
+        }
+    }
+}
+
+impl<W: fmt::Debug + fmt::Display> std::error::Error for Error<W> {}
+
+/// Runtime error together with the state of the computer when it happened.
+#[derive(Eq, PartialEq, Debug)]
+pub struct Fault<W = i64> {
+    pub error: Error<W>,
+    pub instruction_pointer: usize,
+    /// Words of the faulting instruction, just the opcode if it cannot be decoded.
+    pub words: Vec<W>,
+    /// Number of instructions executed before the fault.
+    pub steps: usize,
+    /// Addresses of the most recently executed instructions, oldest first.
+    pub recent: Vec<usize>,
+}
+
+impl<W: Word> fmt::Display for Fault<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let words: Vec<String> = self.words.iter().map(W::to_string).collect();
+        write!(
+            f,
+            "{} at address {} after {} steps, executing {}",
+            self.error,
+            self.instruction_pointer,
+            self.steps,
+            words.join(",")
+        )?;
+        if let Ok(instruction) = Instruction::decode(&self.words) {
+            write!(
+                f,
+                " ({})",
+                format_instruction(&instruction, &Default::default())
+            )?;
+        }
+        if !self.recent.is_empty() {
+            let recent: Vec<String> = self.recent.iter().map(usize::to_string).collect();
+            write!(f, ", recently executed {}", recent.join(" -> "))?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Word + 'static> std::error::Error for Fault<W> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<W> From<decode::Error> for Error<W> {
     fn from(error: decode::Error) -> Self {
         Error::InstructionError { error }
     }
 }
 
-impl Computer {
-    pub fn execute(program: &[i32], input: &[i32]) -> Result<Vec<i32>, Error> {
+impl<W: Word> From<memory::Error> for Error<W> {
+    fn from(error: memory::Error) -> Self {
+        match error {
+            memory::Error::LimitExceeded { limit } => Error::MemoryLimit { limit },
+            memory::Error::AddressOutOfRange { address } => Error::AddressOutOfRange {
+                address: W::from_usize(address).expect("Addresses are converted from words"),
+            },
+        }
+    }
+}
+
+impl<W> From<DeviceError> for Error<W> {
+    fn from(error: DeviceError) -> Self {
+        Error::DeviceError { error }
+    }
+}
+
+impl<W: Word> Computer<W> {
+    pub fn new(program: &[W]) -> Self {
         let mut computer = Computer::default();
         computer.load_program(program);
-        computer.load_input(input);
-        computer.execute_program()?;
+        computer
+    }
+
+    pub fn execute(program: &[W], input: &[W]) -> Result<Vec<W>, Fault<W>> {
+        let mut computer = Self::new(program);
+        computer.input.extend(input.iter().cloned());
+        computer.run()?;
         Ok(computer.output)
     }
 
-    pub fn execute_with_memory_io(program: &[i32], noun: i32, verb: i32) -> Result<i32, Error> {
-        let mut computer = Computer::default();
+    pub fn execute_with_memory_io(program: &[W], noun: W, verb: W) -> Result<W, Fault<W>> {
+        let mut computer = Self::new(program);
+        for (address, value) in [(1, noun), (2, verb)] {
+            if let Err(error) = computer.memory.set(address, value) {
+                return Err(computer.fault(error.into()));
+            }
+        }
+        computer.run()?;
+        Ok(computer.memory.get(0))
+    }
+
+    /// Captures memory, registers and pending input and output.
+    pub fn snapshot(&self) -> Snapshot<W> {
+        Snapshot {
+            instruction_pointer: self.instruction_pointer,
+            relative_base: self.relative_base.clone(),
+            steps: self.steps,
+            recent: self.recent.iter().cloned().collect(),
+            input: self.input.iter().cloned().collect(),
+            output: self.output.clone(),
+            memory_len: self.memory.len(),
+            memory_limit: self.memory.limit(),
+            memory: self
+                .memory
+                .segments()
+                .into_iter()
+                .map(|(address, values)| (address, values.to_vec()))
+                .collect(),
+        }
+    }
+
+    /// Creates a computer continuing from `snapshot`, failing if its memory
+    /// segments lie outside its memory or exceed its memory limit.
+    pub fn restore(snapshot: &Snapshot<W>) -> Result<Self, snapshot::Error> {
+        let segments = snapshot
+            .memory
+            .iter()
+            .map(|(address, values)| (*address, &values[..]));
+        let recent = &snapshot.recent;
+        Ok(Computer {
+            memory: Memory::from_segments(snapshot.memory_len, snapshot.memory_limit, segments)?,
+            output: snapshot.output.clone(),
+            input: snapshot.input.iter().cloned().collect(),
+            instruction_pointer: snapshot.instruction_pointer,
+            relative_base: snapshot.relative_base.clone(),
+            steps: snapshot.steps,
+            recent: recent[recent.len().saturating_sub(RECENT_INSTRUCTIONS)..]
+                .iter()
+                .cloned()
+                .collect(),
+            ..Computer::default()
+        })
+    }
+}
+
+impl<W: Word, O> Computer<W, VecDeque<W>, O> {
+    /// Queues a value for the next input instruction.
+    pub fn push_input(&mut self, value: W) {
+        self.input.push_back(value);
+    }
+}
+
+impl<W: Word, I: Input<W>, O: Output<W>> Computer<W, I, O> {
+    /// Creates a computer reading from `input` and writing to `output`.
+    pub fn with_io(program: &[W], input: I, output: O) -> Self {
+        let mut computer = Computer {
+            memory: Memory::default(),
+            output,
+            input,
+            instruction_pointer: 0,
+            relative_base: W::default(),
+            tracer: None,
+            cache: DecodeCache::default(),
+            steps: 0,
+            recent: VecDeque::new(),
+        };
         computer.load_program(program);
-        computer.memory[1] = noun;
-        computer.memory[2] = verb;
-        computer.execute_program()?;
-        Ok(computer.memory[0])
+        computer
     }
 
-    fn load_program(&mut self, program: &[i32]) {
-        self.memory = program.to_vec();
+    pub fn input(&mut self) -> &mut I {
+        &mut self.input
     }
 
-    fn load_input(&mut self, input: &[i32]) {
-        self.input = input.iter().cloned().rev().collect();
+    pub fn output(&self) -> &O {
+        &self.output
     }
 
-    fn execute_program(&mut self) -> Result<(), Error> {
-        let mut instruction_pointer = 0;
+    pub fn into_output(self) -> O {
+        self.output
+    }
+
+    pub fn memory(&self) -> &Memory<W> {
+        &self.memory
+    }
+
+    pub fn instruction_pointer(&self) -> usize {
+        self.instruction_pointer
+    }
+
+    pub fn relative_base(&self) -> &W {
+        &self.relative_base
+    }
+
+    /// Reports every executed instruction to `tracer`.
+    pub fn set_tracer(&mut self, tracer: Box<dyn Tracer<W>>) {
+        self.tracer = Some(tracer);
+    }
+
+    pub fn take_tracer(&mut self) -> Option<Box<dyn Tracer<W>>> {
+        self.tracer.take()
+    }
+
+    /// Number of instructions executed so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Wraps `error` with the current state of the computer.
+    pub fn fault(&self, error: Error<W>) -> Fault<W> {
+        let (words, _) = self.fetch();
+        let words = match self.current_instruction() {
+            Ok(instruction) => words[..instruction.length].to_vec(),
+            Err(_) => vec![self.memory.get(self.instruction_pointer)],
+        };
+        Fault {
+            error,
+            instruction_pointer: self.instruction_pointer,
+            words,
+            steps: self.steps,
+            recent: self.recent.iter().cloned().collect(),
+        }
+    }
+
+    /// Decodes the instruction the computer executes next.
+    pub fn current_instruction(&self) -> Result<Instruction<W>, decode::Error> {
+        let (words, available) = self.fetch();
+        Instruction::decode(&words[..available])
+    }
+
+    /// Enables or disables caching of decoded instructions, it is disabled by
+    /// default. The cache speeds up loops, but slows down programs that run
+    /// each instruction only once or a few times.
+    pub fn set_decode_cache(&mut self, enabled: bool) {
+        self.cache.set_enabled(enabled);
+    }
+
+    /// Limits the number of memory cells the program may use, `None` removes the limit.
+    pub fn set_memory_limit(&mut self, limit: Option<usize>) {
+        self.memory.set_limit(limit);
+    }
+
+    fn load_program(&mut self, program: &[W]) {
+        self.memory.load(program);
+        self.cache.clear();
+    }
+
+    /// Runs the program until it halts, produces an output or runs out of input.
+    ///
+    /// When input is missing, the instruction pointer stays on the input
+    /// instruction, so the computer can be resumed after pushing more input.
+    pub fn resume(&mut self) -> Result<Status<W>, Fault<W>> {
+        loop {
+            if let Some(status) = self.step()? {
+                return Ok(status);
+            }
+        }
+    }
+
+    /// Runs the program until it halts, writing all values to the output device.
+    pub fn run(&mut self) -> Result<(), Fault<W>> {
         loop {
-            let Instruction {
-                opcode,
-                parameters,
-                length,
-            } = Instruction::decode(&self.memory[instruction_pointer..])?;
-            instruction_pointer += length;
-            match opcode {
-                1 => self.add(&parameters)?,
-                2 => self.multiply(&parameters)?,
-                3 => self.input(&parameters)?,
-                4 => self.output(&parameters)?,
-                5 => self.jump(&mut instruction_pointer, &parameters, |value| value != 0)?,
-                6 => self.jump(&mut instruction_pointer, &parameters, |value| value == 0)?,
-                7 => self.less_than(&parameters)?,
-                8 => self.equals(&parameters)?,
-                99 => return Ok(()),
-                _ => return Err(Error::UnsupportedOperation { opcode }),
+            match self.resume()? {
+                Status::Output(value) => {
+                    if let Err(error) = self.output.write(value) {
+                        return Err(self.fault(error.into()));
+                    }
+                }
+                Status::NeedsInput => return Err(self.fault(Error::MissingInput)),
+                Status::Halted => return Ok(()),
+            }
+        }
+    }
+
+    /// Executes a single instruction, returning a status if execution was interrupted.
+    pub fn step(&mut self) -> Result<Option<Status<W>>, Fault<W>> {
+        let address = self.instruction_pointer;
+        match self.traced_step() {
+            Ok(status @ None) | Ok(status @ Some(Status::Output(_))) => {
+                self.steps += 1;
+                if self.recent.len() == RECENT_INSTRUCTIONS {
+                    self.recent.pop_front();
+                }
+                self.recent.push_back(address);
+                Ok(status)
+            }
+            Ok(status) => Ok(status),
+            Err(error) => Err(self.fault(error)),
+        }
+    }
+
+    fn traced_step(&mut self) -> Result<Option<Status<W>>, Error<W>> {
+        if self.tracer.is_none() {
+            return self.execute_instruction();
+        }
+        let address = self.instruction_pointer;
+        let instruction = self.current_instruction()?;
+        let writes_to =
+            Operation::from_opcode(instruction.opcode).and_then(|operation| operation.writes);
+        let written =
+            writes_to.map(|index| self.address(instruction.opcode, &instruction.parameters[index]));
+        let operands = instruction
+            .parameters
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| Some(*index) != writes_to)
+            .map(|(_, parameter)| self.load(instruction.opcode, parameter))
+            .collect::<Result<Vec<_>, _>>();
+        let status = self.execute_instruction()?;
+        if status == Some(Status::NeedsInput) {
+            return Ok(status);
+        }
+        let writes = match written {
+            Some(address) => {
+                let address = address?;
+                vec![(address, self.memory.get(address))]
+            }
+            None => vec![],
+        };
+        let record = Record {
+            address,
+            instruction,
+            operands: operands?,
+            writes,
+        };
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.trace(&record);
+        }
+        Ok(status)
+    }
+
+    fn execute_instruction(&mut self) -> Result<Option<Status<W>>, Error<W>> {
+        let decoded = self.decode()?;
+        let (opcode, parameters) = (decoded.opcode, decoded.parameters());
+        let mut next_instruction = self
+            .instruction_pointer
+            .checked_add(decoded.length)
+            .ok_or(Error::Overflow { opcode })?;
+        let mut status = None;
+        match opcode {
+            1 => self.binary_operation(opcode, parameters, W::checked_add)?,
+            2 => self.binary_operation(opcode, parameters, W::checked_mul)?,
+            3 => {
+                if !self.read_input(opcode, parameters)? {
+                    return Ok(Some(Status::NeedsInput));
+                }
+            }
+            4 => status = Some(Status::Output(self.write_output(opcode, parameters)?)),
+            5 => self.jump(opcode, &mut next_instruction, parameters, |value| {
+                !value.is_zero()
+            })?,
+            6 => self.jump(opcode, &mut next_instruction, parameters, W::is_zero)?,
+            7 => self.binary_operation(opcode, parameters, |a, b| Some(W::from_bool(a < b)))?,
+            8 => self.binary_operation(opcode, parameters, |a, b| Some(W::from_bool(a == b)))?,
+            9 => self.adjust_relative_base(opcode, parameters)?,
+            99 => return Ok(Some(Status::Halted)),
+            _ => return Err(Error::UnsupportedOperation { opcode }),
+        }
+        self.instruction_pointer = next_instruction;
+        Ok(status)
+    }
+
+    fn decode(&mut self) -> Result<Decoded<W>, Error<W>> {
+        let address = self.instruction_pointer;
+        if let Some(decoded) = self.cache.get(address) {
+            return Ok(decoded.clone());
+        }
+        let (words, available) = self.fetch();
+        let decoded = Decoded::decode(&words[..available])?;
+        self.cache.insert(address, decoded.clone());
+        Ok(decoded)
+    }
+
+    /// Words from the instruction pointer on, with the number of them that
+    /// lie within the address space.
+    fn fetch(&self) -> ([W; 4], usize) {
+        let mut words: [W; 4] = Default::default();
+        let mut available = 0;
+        for (offset, word) in words.iter_mut().enumerate() {
+            match self.instruction_pointer.checked_add(offset) {
+                Some(address) => *word = self.memory.get(address),
+                None => break,
             }
+            available += 1;
         }
+        (words, available)
     }
 
     fn jump(
         &self,
+        opcode: usize,
         instruction_pointer: &mut usize,
-        parameters: &[Parameter],
-        predicate: fn(i32) -> bool,
-    ) -> Result<(), Error> {
-        if predicate(self.load(parameters[0])?) {
-            *instruction_pointer = self.load(parameters[1])? as usize
+        parameters: &[Parameter<W>],
+        predicate: fn(&W) -> bool,
+    ) -> Result<(), Error<W>> {
+        if predicate(&self.load(opcode, &parameters[0])?) {
+            let target = self.load(opcode, &parameters[1])?;
+            *instruction_pointer = target
+                .to_usize()
+                .ok_or(Error::AddressOutOfRange { address: target })?;
         }
         Ok(())
     }
 
-    fn store(&mut self, parameter: Parameter, value: i32) -> Result<(), Error> {
-        if let Parameter::Address { address } = parameter {
-            self.memory[address] = value;
-            Ok(())
-        } else {
-            Err(Error::no_address(parameter))
+    /// Address `parameter` of an instruction with `opcode` refers to.
+    fn address(&self, opcode: usize, parameter: &Parameter<W>) -> Result<usize, Error<W>> {
+        match parameter {
+            Parameter::Address { address } => Ok(*address),
+            Parameter::Relative { offset } => {
+                let address = self
+                    .relative_base
+                    .checked_add(offset)
+                    .ok_or(Error::Overflow { opcode })?;
+                address
+                    .to_usize()
+                    .ok_or(Error::AddressOutOfRange { address })
+            }
+            Parameter::Value { .. } => Err(Error::no_address(parameter.clone())),
         }
     }
 
-    fn load(&self, parameter: Parameter) -> Result<i32, Error> {
-        match parameter {
-            Parameter::Address { address } => self
-                .memory
-                .get(address)
-                .cloned()
-                .ok_or(Error::InvalidAddress { address }),
-            Parameter::Value { value } => Ok(value),
+    fn store(&mut self, opcode: usize, parameter: &Parameter<W>, value: W) -> Result<(), Error<W>> {
+        let address = self.address(opcode, parameter)?;
+        self.memory.set(address, value)?;
+        self.cache.invalidate(address);
+        Ok(())
+    }
+
+    fn load(&self, opcode: usize, parameter: &Parameter<W>) -> Result<W, Error<W>> {
+        if let Parameter::Value { value } = parameter {
+            return Ok(value.clone());
         }
+        Ok(self.memory.get(self.address(opcode, parameter)?))
     }
 
     fn binary_operation(
         &mut self,
-        parameters: &[Parameter],
-        operation: fn(i32, i32) -> i32,
-    ) -> Result<(), Error> {
+        opcode: usize,
+        parameters: &[Parameter<W>],
+        operation: fn(&W, &W) -> Option<W>,
+    ) -> Result<(), Error<W>> {
         if parameters.len() == 3 {
-            self.store(
-                parameters[2],
-                operation(self.load(parameters[0])?, self.load(parameters[1])?),
+            let result = operation(
+                &self.load(opcode, &parameters[0])?,
+                &self.load(opcode, &parameters[1])?,
             )
+            .ok_or(Error::Overflow { opcode })?;
+            self.store(opcode, &parameters[2], result)
         } else {
             Err(Error::ParameterCount {
                 expected: 3,
@@ -130,26 +544,31 @@ impl Computer {
         }
     }
 
-    fn add(&mut self, parameters: &[Parameter]) -> Result<(), Error> {
-        self.binary_operation(parameters, |a, b| a + b)
-    }
-
-    fn multiply(&mut self, parameters: &[Parameter]) -> Result<(), Error> {
-        self.binary_operation(parameters, |a, b| a * b)
-    }
-
-    fn equals(&mut self, parameters: &[Parameter]) -> Result<(), Error> {
-        self.binary_operation(parameters, |a, b| if a == b { 1 } else { 0 })
-    }
-
-    fn less_than(&mut self, parameters: &[Parameter]) -> Result<(), Error> {
-        self.binary_operation(parameters, |a, b| if a < b { 1 } else { 0 })
+    fn read_input(&mut self, opcode: usize, parameters: &[Parameter<W>]) -> Result<bool, Error<W>> {
+        if parameters.len() == 1 {
+            match self.input.read()? {
+                Some(input) => self.store(opcode, &parameters[0], input).map(|_| true),
+                None => Ok(false),
+            }
+        } else {
+            Err(Error::ParameterCount {
+                expected: 1,
+                got: parameters.len(),
+            })
+        }
     }
 
-    fn input(&mut self, parameters: &[Parameter]) -> Result<(), Error> {
+    fn adjust_relative_base(
+        &mut self,
+        opcode: usize,
+        parameters: &[Parameter<W>],
+    ) -> Result<(), Error<W>> {
         if parameters.len() == 1 {
-            let input = self.input.pop().ok_or(Error::MissingInput)?;
-            self.store(parameters[0], input)
+            self.relative_base = self
+                .relative_base
+                .checked_add(&self.load(opcode, &parameters[0])?)
+                .ok_or(Error::Overflow { opcode })?;
+            Ok(())
         } else {
             Err(Error::ParameterCount {
                 expected: 1,
@@ -158,10 +577,9 @@ impl Computer {
         }
     }
 
-    fn output(&mut self, parameters: &[Parameter]) -> Result<(), Error> {
+    fn write_output(&self, opcode: usize, parameters: &[Parameter<W>]) -> Result<W, Error<W>> {
         if parameters.len() == 1 {
-            self.output.push(self.load(parameters[0])?);
-            Ok(())
+            self.load(opcode, &parameters[0])
         } else {
             Err(Error::ParameterCount {
                 expected: 1,
@@ -174,52 +592,56 @@ impl Computer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::intcode::device::FromIter;
+    use std::sync::mpsc::channel;
+
+    type Computer = super::Computer<i64>;
 
     #[test]
     fn opcode_1_adds_arguments() {
         let mut computer = Computer::default();
         computer.load_program(&[1, 0, 0, 0, 99]);
-        computer.execute_program().expect("Execution failed");
-        assert_eq!(computer.memory, vec![2, 0, 0, 0, 99]);
+        computer.run().expect("Execution failed");
+        assert_eq!(computer.memory.to_vec(), vec![2, 0, 0, 0, 99]);
     }
 
     #[test]
     fn opcode_2_multiplies_arguments() {
         let mut computer = Computer::default();
         computer.load_program(&[2, 3, 0, 3, 99]);
-        computer.execute_program().expect("Execution failed");
-        assert_eq!(computer.memory, vec![2, 3, 0, 6, 99]);
+        computer.run().expect("Execution failed");
+        assert_eq!(computer.memory.to_vec(), vec![2, 3, 0, 6, 99]);
     }
 
     #[test]
     fn opcode_99_terminates() {
         let mut computer = Computer::default();
         computer.load_program(&[2, 4, 4, 5, 99, 0]);
-        computer.execute_program().expect("Execution failed");
-        assert_eq!(computer.memory, vec![2, 4, 4, 5, 99, 9801]);
+        computer.run().expect("Execution failed");
+        assert_eq!(computer.memory.to_vec(), vec![2, 4, 4, 5, 99, 9801]);
     }
 
     #[test]
     fn programs_can_self_modify() {
         let mut computer = Computer::default();
         computer.load_program(&[1, 1, 1, 4, 99, 5, 6, 0, 99]);
-        computer.execute_program().expect("Execution failed");
-        assert_eq!(computer.memory, vec![30, 1, 1, 4, 2, 5, 6, 0, 99]);
+        computer.run().expect("Execution failed");
+        assert_eq!(computer.memory.to_vec(), vec![30, 1, 1, 4, 2, 5, 6, 0, 99]);
     }
 
     #[test]
     fn supports_negative_numbers() {
         let mut computer = Computer::default();
         computer.load_program(&[1, 5, 6, 0, 99, 5, -6]);
-        computer.execute_program().expect("Execution failed");
-        assert_eq!(computer.memory, vec![-1, 5, 6, 0, 99, 5, -6]);
+        computer.run().expect("Execution failed");
+        assert_eq!(computer.memory.to_vec(), vec![-1, 5, 6, 0, 99, 5, -6]);
     }
 
     #[test]
     fn supports_output() {
         let mut computer = Computer::default();
         computer.load_program(&[4, 3, 99, 23]);
-        computer.execute_program().expect("Execution failed");
+        computer.run().expect("Execution failed");
         assert_eq!(computer.output, &[23]);
     }
 
@@ -227,18 +649,18 @@ mod tests {
     fn supports_input() {
         let mut computer = Computer::default();
         computer.load_program(&[3, 0, 99]);
-        computer.input.push(13);
-        computer.execute_program().expect("Execution failed");
-        assert_eq!(computer.memory, &[13, 0, 99]);
+        computer.push_input(13);
+        computer.run().expect("Execution failed");
+        assert_eq!(computer.memory.to_vec(), &[13, 0, 99]);
     }
 
     #[test]
     fn can_echo() {
         let mut computer = Computer::default();
         computer.load_program(&[3, 0, 4, 0, 99]);
-        computer.input.push(13);
-        computer.execute_program().expect("Execution failed");
-        assert_eq!(computer.memory, &[13, 0, 4, 0, 99]);
+        computer.push_input(13);
+        computer.run().expect("Execution failed");
+        assert_eq!(computer.memory.to_vec(), &[13, 0, 4, 0, 99]);
         assert_eq!(computer.output, &[13]);
     }
 
@@ -276,4 +698,291 @@ mod tests {
             Ok(vec![1])
         );
     }
+
+    #[test]
+    fn pauses_on_missing_input() {
+        let mut computer = Computer::new(&[3, 0, 4, 0, 99]);
+        assert_eq!(computer.resume(), Ok(Status::NeedsInput));
+        assert_eq!(computer.resume(), Ok(Status::NeedsInput));
+        computer.push_input(42);
+        assert_eq!(computer.resume(), Ok(Status::Output(42)));
+        assert_eq!(computer.resume(), Ok(Status::Halted));
+    }
+
+    #[test]
+    fn resumes_after_each_output() {
+        let mut computer = Computer::new(&[4, 5, 4, 6, 99, 7, 8]);
+        assert_eq!(computer.resume(), Ok(Status::Output(7)));
+        assert_eq!(computer.resume(), Ok(Status::Output(8)));
+        assert_eq!(computer.resume(), Ok(Status::Halted));
+        assert_eq!(computer.resume(), Ok(Status::Halted));
+    }
+
+    #[test]
+    fn supports_relative_base() {
+        let mut computer = Computer::new(&[109, 7, 204, -3, 21101, 3, 4, 2, 99, 0]);
+        assert_eq!(computer.resume(), Ok(Status::Output(21101)));
+        assert_eq!(computer.resume(), Ok(Status::Halted));
+        assert_eq!(computer.memory.get(9), 7);
+    }
+
+    #[test]
+    fn rejects_negative_relative_address() {
+        assert_eq!(
+            Computer::execute(&[204, -1, 99], &[]).map_err(|fault| fault.error),
+            Err(Error::AddressOutOfRange { address: -1 })
+        );
+    }
+
+    #[test]
+    fn reports_overflow() {
+        assert_eq!(
+            Computer::execute(&[1102, i64::MAX, 2, 0, 99], &[]).map_err(|fault| fault.error),
+            Err(Error::Overflow { opcode: 2 })
+        );
+        assert_eq!(
+            Computer::execute(&[1101, i64::MAX, 1, 0, 99], &[]).map_err(|fault| fault.error),
+            Err(Error::Overflow { opcode: 1 })
+        );
+        assert_eq!(
+            Computer::execute(&[109, i64::MAX, 1201, 1, 0, 0, 99], &[])
+                .map_err(|fault| fault.error),
+            Err(Error::Overflow { opcode: 1 })
+        );
+        assert_eq!(
+            Computer::execute(&[109, i64::MAX, 204, 1, 99], &[]).map_err(|fault| fault.error),
+            Err(Error::Overflow { opcode: 4 })
+        );
+    }
+
+    #[test]
+    fn stops_at_end_of_address_space() {
+        let last = usize::MAX as i128;
+        assert_eq!(
+            super::Computer::<i128>::execute(&[1101, 1, 1, last, 99], &[])
+                .map_err(|fault| fault.error),
+            Err(Error::AddressOutOfRange { address: last })
+        );
+        let program = [1101, 1, 0, last - 2, 1106, 0, last - 2];
+        let fault = super::Computer::<i128>::execute(&program, &[]).unwrap_err();
+        assert_eq!(
+            fault.error,
+            Error::InstructionError {
+                error: decode::Error::Truncated {
+                    length: 4,
+                    available: 3
+                }
+            }
+        );
+        assert_eq!(fault.words, vec![1]);
+    }
+
+    #[test]
+    fn supports_large_numbers() {
+        assert_eq!(
+            Computer::execute(&[1102, 34_915_192, 34_915_192, 7, 4, 7, 99, 0], &[]),
+            Ok(vec![1_219_070_632_396_864])
+        );
+        assert_eq!(
+            Computer::execute(&[104, 1_125_899_906_842_624, 99], &[]),
+            Ok(vec![1_125_899_906_842_624])
+        );
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn supports_big_integers() {
+        use num_bigint::BigInt;
+        let program: Vec<BigInt> = vec![1102, i64::MAX, i64::MAX, 7, 4, 7, 99, 0]
+            .into_iter()
+            .map(BigInt::from)
+            .collect();
+        assert_eq!(
+            super::Computer::execute(&program, &[]),
+            Ok(vec![BigInt::from(i64::MAX) * BigInt::from(i64::MAX)])
+        );
+    }
+
+    #[test]
+    fn memory_grows_beyond_program() {
+        assert_eq!(
+            Computer::execute(&[1101, 2, 3, 1000, 4, 1000, 4, 2000, 99], &[]),
+            Ok(vec![5, 0])
+        );
+    }
+
+    #[test]
+    fn produces_copy_of_itself() {
+        let program = [
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        assert_eq!(Computer::execute(&program, &[]), Ok(program.to_vec()));
+    }
+
+    #[test]
+    fn stops_at_memory_limit() {
+        let mut computer = Computer::new(&[1101, 2, 3, 1_000_000, 99]);
+        computer.set_memory_limit(Some(1024));
+        assert_eq!(
+            computer.resume().map_err(|fault| fault.error),
+            Err(Error::MemoryLimit { limit: 1024 })
+        );
+    }
+
+    #[test]
+    fn reads_and_writes_devices() {
+        let mut outputs = Vec::new();
+        let mut computer = super::Computer::with_io(
+            &[3, 0, 4, 0, 3, 0, 4, 0, 99],
+            FromIter(vec![3, 5].into_iter()),
+            |value| outputs.push(value * 2),
+        );
+        computer.run().expect("Execution failed");
+        drop(computer);
+        assert_eq!(outputs, vec![6, 10]);
+    }
+
+    #[test]
+    fn connects_computers_with_channels() {
+        let (sender, receiver) = channel();
+        let (result_sender, result_receiver) = channel();
+        let mut first = super::Computer::with_io(&[104, 20, 99], VecDeque::new(), sender);
+        let mut second =
+            super::Computer::with_io(&[3, 9, 1002, 9, 2, 9, 4, 9, 99, 0], receiver, result_sender);
+        first.run().expect("Execution failed");
+        drop(first);
+        second.run().expect("Execution failed");
+        assert_eq!(result_receiver.recv(), Ok(40));
+    }
+
+    #[test]
+    fn device_errors_abort_execution() {
+        let (sender, receiver) = channel::<i64>();
+        drop(receiver);
+        let mut computer = super::Computer::with_io(&[104, 1, 99], VecDeque::new(), sender);
+        assert_eq!(
+            computer.run().map_err(|fault| fault.error),
+            Err(Error::DeviceError {
+                error: DeviceError {
+                    message: "receiver disconnected".to_string()
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn faults_carry_execution_context() {
+        let fault = Computer::execute(&[1101, 1, 1, 20, 1102, i64::MAX, 2, 0, 99], &[])
+            .expect_err("Execution succeeded");
+        assert_eq!(
+            fault,
+            Fault {
+                error: Error::Overflow { opcode: 2 },
+                instruction_pointer: 4,
+                words: vec![1102, i64::MAX, 2, 0],
+                steps: 1,
+                recent: vec![0],
+            }
+        );
+        assert_eq!(
+            fault.to_string(),
+            "overflow in opcode 2 at address 4 after 1 steps, executing \
+             1102,9223372036854775807,2,0 (mul #9223372036854775807, #2 -> [0]), \
+             recently executed 0"
+        );
+    }
+
+    #[test]
+    fn faults_keep_recent_instructions() {
+        let mut program: Vec<i64> = (0..10)
+            .flat_map(|index| vec![1106, 0, 3 * index + 3])
+            .collect();
+        program.push(77);
+        let fault = Computer::execute(&program, &[]).expect_err("Execution succeeded");
+        assert_eq!(
+            fault.error,
+            Error::InstructionError {
+                error: decode::Error::InvalidOpcode { opcode: 77 }
+            }
+        );
+        assert_eq!(fault.words, vec![77]);
+        assert_eq!(fault.steps, 10);
+        assert_eq!(fault.recent, vec![6, 9, 12, 15, 18, 21, 24, 27]);
+        assert!(fault
+            .to_string()
+            .starts_with("invalid opcode 77 at address 30 after 10 steps, executing 77,"));
+    }
+
+    #[test]
+    fn cached_instructions_see_self_modification() {
+        let mut computer = Computer::new(&[
+            1101, 0, 0, 20, 4, 20, 1101, 5, 0, 2, 1007, 20, 1, 21, 1005, 21, 0, 99,
+        ]);
+        computer.set_decode_cache(true);
+        assert_eq!(computer.resume(), Ok(Status::Output(0)));
+        assert_eq!(computer.resume(), Ok(Status::Output(5)));
+        assert_eq!(computer.resume(), Ok(Status::Halted));
+    }
+
+    #[test]
+    fn clones_branch_from_common_state() {
+        let mut computer = Computer::new(&[104, 1, 3, 11, 1002, 11, 3, 11, 4, 11, 99, 0]);
+        assert_eq!(computer.resume(), Ok(Status::Output(1)));
+        assert_eq!(computer.resume(), Ok(Status::NeedsInput));
+        let outputs: Vec<_> = (1..=3)
+            .map(|input| {
+                let mut branch = computer.clone();
+                branch.push_input(input);
+                branch.resume()
+            })
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                Ok(Status::Output(3)),
+                Ok(Status::Output(6)),
+                Ok(Status::Output(9))
+            ]
+        );
+    }
+
+    #[test]
+    fn restores_snapshots() {
+        let mut computer = Computer::new(&[109, 5, 203, 6, 4, 11, 99, 0, 0, 0, 0, 0]);
+        computer.push_input(7);
+        computer.push_input(8);
+        assert_eq!(computer.resume(), Ok(Status::Output(7)));
+        let snapshot = computer.snapshot();
+        assert_eq!(snapshot.instruction_pointer, 6);
+        assert_eq!(snapshot.relative_base, 5);
+        assert_eq!(snapshot.input, vec![8]);
+        assert_eq!((snapshot.steps, &snapshot.recent[..]), (3, &[0, 2, 4][..]));
+        let mut restored = Computer::restore(&snapshot).unwrap();
+        assert_eq!(restored.snapshot(), snapshot);
+        assert_eq!(restored.memory.to_vec(), computer.memory.to_vec());
+        assert_eq!(restored.resume(), Ok(Status::Halted));
+
+        computer.set_memory_limit(None);
+        let unlimited = Computer::restore(&computer.snapshot()).unwrap();
+        assert_eq!(unlimited.memory.limit(), None);
+    }
+
+    #[test]
+    fn rejects_invalid_snapshots() {
+        let mut snapshot = Computer::new(&[99]).snapshot();
+        snapshot.memory.push((usize::MAX, vec![1, 2]));
+        assert!(matches!(
+            Computer::restore(&snapshot),
+            Err(snapshot::Error::InvalidSegment {
+                address: usize::MAX
+            })
+        ));
+        snapshot.memory = vec![(0, vec![1; 2048])];
+        snapshot.memory_len = 2048;
+        snapshot.memory_limit = Some(1024);
+        assert!(matches!(
+            Computer::restore(&snapshot),
+            Err(snapshot::Error::MemoryLimit { limit: 1024 })
+        ));
+    }
 }
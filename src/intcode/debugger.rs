@@ -0,0 +1,402 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use super::computer::{Computer, Status};
+use super::decode::Operation;
+use super::disasm::format_instruction;
+use super::word::Word;
+
+const WORDS_PER_ROW: usize = 8;
+
+pub const HELP: &str = "\
+step [n]            execute n instructions (default 1)
+continue            run until a breakpoint, watchpoint, halt or missing input
+break <addr>        stop before executing the instruction at addr
+break op <op>       stop before executing an opcode or mnemonic
+delete <addr>       remove a breakpoint, `delete op <op>` an opcode breakpoint
+watch <addr>        stop when the memory cell at addr changes
+unwatch <addr>      remove a watchpoint
+registers           show instruction pointer, relative base and next instruction
+memory <addr> [n]   dump n memory cells (default 16)
+input <values>      queue values for input instructions
+queues              show pending input and produced output
+info                list breakpoints and watchpoints
+quit                leave the debugger";
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Command<W = i64> {
+    Step(usize),
+    Continue,
+    Break(usize),
+    BreakOpcode(usize),
+    Delete(usize),
+    DeleteOpcode(usize),
+    Watch(usize),
+    Unwatch(usize),
+    Registers,
+    Memory { start: usize, count: usize },
+    Input(Vec<W>),
+    Queues,
+    Info,
+    Help,
+    Quit,
+}
+
+impl<W: Word> Command<W> {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |index: usize| -> Result<usize, String> {
+            let word = words
+                .get(index)
+                .ok_or_else(|| format!("{} needs an argument", words[0]))?;
+            word.parse()
+                .map_err(|_| format!("{} is not a valid number", word))
+        };
+        let opcode = |index: usize| -> Result<usize, String> {
+            let word = words
+                .get(index)
+                .ok_or_else(|| format!("{} needs an opcode", words[0]))?;
+            Operation::from_mnemonic(word)
+                .map(|operation| operation.opcode)
+                .or_else(|| word.parse().ok())
+                .ok_or_else(|| format!("{} is not a valid opcode", word))
+        };
+        match words.as_slice() {
+            [] => Err("Empty command".to_string()),
+            ["s"] | ["step"] => Ok(Command::Step(1)),
+            ["s", _] | ["step", _] => Ok(Command::Step(number(1)?)),
+            ["c"] | ["continue"] => Ok(Command::Continue),
+            ["b", "op", _] | ["break", "op", _] => Ok(Command::BreakOpcode(opcode(2)?)),
+            ["b", _] | ["break", _] => Ok(Command::Break(number(1)?)),
+            ["d", "op", _] | ["delete", "op", _] => Ok(Command::DeleteOpcode(opcode(2)?)),
+            ["d", _] | ["delete", _] => Ok(Command::Delete(number(1)?)),
+            ["w", _] | ["watch", _] => Ok(Command::Watch(number(1)?)),
+            ["unwatch", _] => Ok(Command::Unwatch(number(1)?)),
+            ["r"] | ["registers"] => Ok(Command::Registers),
+            ["m", _] | ["memory", _] => Command::memory(number(1)?, 16),
+            ["m", _, _] | ["memory", _, _] => Command::memory(number(1)?, number(2)?),
+            ["i", values @ ..] | ["input", values @ ..] if !values.is_empty() => values
+                .iter()
+                .map(|value| {
+                    value
+                        .parse()
+                        .map_err(|_| format!("{} is not a valid value", value))
+                })
+                .collect::<Result<_, _>>()
+                .map(Command::Input),
+            ["queues"] => Ok(Command::Queues),
+            ["info"] => Ok(Command::Info),
+            ["h"] | ["help"] => Ok(Command::Help),
+            ["q"] | ["quit"] => Ok(Command::Quit),
+            _ => Err(format!("Unknown command: {}", line.trim())),
+        }
+    }
+
+    fn memory(start: usize, count: usize) -> Result<Self, String> {
+        match start.checked_add(count) {
+            Some(_) => Ok(Command::Memory { start, count }),
+            None => Err(format!(
+                "{} cells from {} exceed the address space",
+                count, start
+            )),
+        }
+    }
+}
+
+/// Interactive stepping through a program with breakpoints and watchpoints.
+pub struct Debugger<W = i64> {
+    computer: Computer<W>,
+    output: Vec<W>,
+    breakpoints: BTreeSet<usize>,
+    opcode_breakpoints: BTreeSet<usize>,
+    watchpoints: BTreeMap<usize, W>,
+    finished: bool,
+}
+
+impl<W: Word> Debugger<W> {
+    pub fn new(program: &[W]) -> Self {
+        Debugger {
+            computer: Computer::new(program),
+            output: Vec::new(),
+            breakpoints: BTreeSet::new(),
+            opcode_breakpoints: BTreeSet::new(),
+            watchpoints: BTreeMap::new(),
+            finished: false,
+        }
+    }
+
+    /// Executes a command and returns the text to show to the user.
+    pub fn execute(&mut self, command: Command<W>) -> String {
+        let mut report = String::new();
+        match command {
+            Command::Step(count) => {
+                for _ in 0..count {
+                    if !self.step(&mut report) {
+                        break;
+                    }
+                }
+                self.describe_position(&mut report);
+            }
+            Command::Continue => {
+                if self.step(&mut report) {
+                    while !self.at_breakpoint(&mut report) && self.step(&mut report) {}
+                }
+                self.describe_position(&mut report);
+            }
+            Command::Break(address) => {
+                self.breakpoints.insert(address);
+                writeln!(report, "Breakpoint at {}", address).unwrap();
+            }
+            Command::BreakOpcode(opcode) => {
+                self.opcode_breakpoints.insert(opcode);
+                writeln!(report, "Breakpoint on opcode {}", opcode).unwrap();
+            }
+            Command::Delete(address) => {
+                self.breakpoints.remove(&address);
+            }
+            Command::DeleteOpcode(opcode) => {
+                self.opcode_breakpoints.remove(&opcode);
+            }
+            Command::Watch(address) => {
+                let value = self.computer.memory().get(address);
+                writeln!(report, "Watching [{}] = {}", address, value).unwrap();
+                self.watchpoints.insert(address, value);
+            }
+            Command::Unwatch(address) => {
+                self.watchpoints.remove(&address);
+            }
+            Command::Registers => {
+                writeln!(
+                    report,
+                    "ip = {}, relative base = {}",
+                    self.computer.instruction_pointer(),
+                    self.computer.relative_base()
+                )
+                .unwrap();
+                self.describe_position(&mut report);
+            }
+            Command::Memory { start, count } => {
+                let memory = self.computer.memory();
+                let last = start.saturating_add(count);
+                for row in (start..last).step_by(WORDS_PER_ROW) {
+                    let end = row.saturating_add(WORDS_PER_ROW).min(last);
+                    let values: Vec<String> = (row..end)
+                        .map(|address| memory.get(address).to_string())
+                        .collect();
+                    writeln!(report, "{:>6}: {}", row, values.join(" ")).unwrap();
+                }
+            }
+            Command::Input(values) => {
+                for value in values {
+                    self.computer.push_input(value);
+                }
+            }
+            Command::Queues => {
+                let input: Vec<String> = self.computer.input().iter().map(W::to_string).collect();
+                let output: Vec<String> = self.output.iter().map(W::to_string).collect();
+                writeln!(report, "input:  [{}]", input.join(", ")).unwrap();
+                writeln!(report, "output: [{}]", output.join(", ")).unwrap();
+            }
+            Command::Info => {
+                for address in self.breakpoints.iter() {
+                    writeln!(report, "break {}", address).unwrap();
+                }
+                for opcode in self.opcode_breakpoints.iter() {
+                    writeln!(report, "break op {}", opcode).unwrap();
+                }
+                for (address, value) in self.watchpoints.iter() {
+                    writeln!(report, "watch {} = {}", address, value).unwrap();
+                }
+            }
+            Command::Help => writeln!(report, "{}", HELP).unwrap(),
+            Command::Quit => {}
+        }
+        report
+    }
+
+    pub fn output(&self) -> &[W] {
+        &self.output
+    }
+
+    /// Executes one instruction, returns whether execution may go on.
+    fn step(&mut self, report: &mut String) -> bool {
+        if self.finished {
+            writeln!(report, "Program is not running").unwrap();
+            return false;
+        }
+        let address = self.computer.instruction_pointer();
+        let running = match self.computer.step() {
+            Ok(None) => true,
+            Ok(Some(Status::Output(value))) => {
+                writeln!(report, "Output: {}", value).unwrap();
+                self.output.push(value);
+                true
+            }
+            Ok(Some(Status::NeedsInput)) => {
+                writeln!(report, "Waiting for input at {}", address).unwrap();
+                false
+            }
+            Ok(Some(Status::Halted)) => {
+                writeln!(report, "Halted at {}", address).unwrap();
+                self.finished = true;
+                false
+            }
+            Err(fault) => {
+                writeln!(report, "Error at {}: {}", address, fault.error).unwrap();
+                self.finished = true;
+                false
+            }
+        };
+        self.check_watchpoints(report) && running
+    }
+
+    fn check_watchpoints(&mut self, report: &mut String) -> bool {
+        let memory = self.computer.memory();
+        let mut unchanged = true;
+        for (address, value) in self.watchpoints.iter_mut() {
+            let current = memory.get(*address);
+            if current != *value {
+                writeln!(report, "Watchpoint [{}]: {} -> {}", address, value, current).unwrap();
+                *value = current;
+                unchanged = false;
+            }
+        }
+        unchanged
+    }
+
+    fn at_breakpoint(&self, report: &mut String) -> bool {
+        let address = self.computer.instruction_pointer();
+        if self.breakpoints.contains(&address) {
+            writeln!(report, "Breakpoint at {}", address).unwrap();
+            return true;
+        }
+        match self.computer.current_instruction() {
+            Ok(instruction) if self.opcode_breakpoints.contains(&instruction.opcode) => {
+                writeln!(report, "Breakpoint on opcode {}", instruction.opcode).unwrap();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn describe_position(&self, report: &mut String) {
+        if self.finished {
+            return;
+        }
+        let address = self.computer.instruction_pointer();
+        match self.computer.current_instruction() {
+            Ok(instruction) => writeln!(
+                report,
+                "{:>6}: {}",
+                address,
+                format_instruction(&instruction, &BTreeSet::new())
+            ),
+            Err(error) => writeln!(report, "{:>6}: {}", address, error),
+        }
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTDOWN: [i64; 13] = [3, 12, 4, 12, 1001, 12, -1, 12, 1005, 12, 2, 99, 0];
+
+    fn run(debugger: &mut Debugger, command: &str) -> String {
+        debugger.execute(Command::parse(command).unwrap())
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(Command::<i64>::parse("step 3"), Ok(Command::Step(3)));
+        assert_eq!(
+            Command::<i64>::parse("b op mul"),
+            Ok(Command::BreakOpcode(2))
+        );
+        assert_eq!(
+            Command::<i64>::parse("break op 7"),
+            Ok(Command::BreakOpcode(7))
+        );
+        assert_eq!(
+            Command::parse("input 1 -2"),
+            Ok(Command::Input(vec![1, -2]))
+        );
+        assert_eq!(
+            Command::<i64>::parse("memory 4 2"),
+            Ok(Command::Memory { start: 4, count: 2 })
+        );
+        assert_eq!(
+            Command::<i64>::parse("memory 18446744073709551615 16"),
+            Err("16 cells from 18446744073709551615 exceed the address space".to_string())
+        );
+        assert!(Command::<i64>::parse("break x").is_err());
+        assert!(Command::<i64>::parse("jump").is_err());
+    }
+
+    #[test]
+    fn steps_and_waits_for_input() {
+        let mut debugger = Debugger::new(&COUNTDOWN);
+        assert_eq!(
+            run(&mut debugger, "step"),
+            "Waiting for input at 0\n     0: in -> [12]\n"
+        );
+        run(&mut debugger, "input 2");
+        assert_eq!(
+            run(&mut debugger, "step 2"),
+            "Output: 2\n     4: add [12], #-1 -> [12]\n"
+        );
+        assert_eq!(
+            run(&mut debugger, "registers"),
+            "ip = 4, relative base = 0\n     4: add [12], #-1 -> [12]\n"
+        );
+    }
+
+    #[test]
+    fn stops_at_breakpoints() {
+        let mut debugger = Debugger::new(&COUNTDOWN);
+        run(&mut debugger, "input 2");
+        run(&mut debugger, "break 8");
+        assert_eq!(
+            run(&mut debugger, "continue"),
+            "Output: 2\nBreakpoint at 8\n     8: jnz [12], #2\n"
+        );
+        run(&mut debugger, "delete 8");
+        run(&mut debugger, "break op out");
+        assert_eq!(
+            run(&mut debugger, "c"),
+            "Breakpoint on opcode 4\n     2: out [12]\n"
+        );
+        run(&mut debugger, "d op out");
+        assert_eq!(run(&mut debugger, "c"), "Output: 1\nHalted at 11\n");
+        assert_eq!(debugger.output(), &[2, 1]);
+        assert_eq!(run(&mut debugger, "step"), "Program is not running\n");
+    }
+
+    #[test]
+    fn stops_at_watchpoints() {
+        let mut debugger = Debugger::new(&COUNTDOWN);
+        run(&mut debugger, "input 3");
+        assert_eq!(run(&mut debugger, "watch 12"), "Watching [12] = 0\n");
+        assert_eq!(
+            run(&mut debugger, "continue"),
+            "Watchpoint [12]: 0 -> 3\n     2: out [12]\n"
+        );
+        assert_eq!(
+            run(&mut debugger, "continue"),
+            "Output: 3\nWatchpoint [12]: 3 -> 2\n     8: jnz [12], #2\n"
+        );
+        assert_eq!(run(&mut debugger, "info"), "watch 12 = 2\n");
+    }
+
+    #[test]
+    fn dumps_memory_and_queues() {
+        let mut debugger = Debugger::new(&COUNTDOWN);
+        assert_eq!(
+            run(&mut debugger, "memory 0 10"),
+            "     0: 3 12 4 12 1001 12 -1 12\n     8: 1005 12\n"
+        );
+        run(&mut debugger, "input 1 2");
+        assert_eq!(run(&mut debugger, "queues"), "input:  [1, 2]\noutput: []\n");
+    }
+}
@@ -1,46 +1,204 @@
+use std::fmt;
+
+use super::word::Word;
+
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
-pub enum Parameter {
+pub enum Parameter<W = i64> {
     Address { address: usize },
-    Value { value: i32 },
+    Value { value: W },
+    Relative { offset: W },
 }
 
-impl Parameter {
+impl<W> Parameter<W> {
     fn address(address: usize) -> Self {
         Parameter::Address { address }
     }
 
-    fn value(value: i32) -> Self {
+    fn value(value: W) -> Self {
         Parameter::Value { value }
     }
+
+    fn relative(offset: W) -> Self {
+        Parameter::Relative { offset }
+    }
+
+    pub fn mode(&self) -> usize {
+        match self {
+            Parameter::Address { .. } => 0,
+            Parameter::Value { .. } => 1,
+            Parameter::Relative { .. } => 2,
+        }
+    }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub struct Instruction {
+/// Static properties of an operation.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Operation {
+    pub opcode: usize,
+    pub mnemonic: &'static str,
+    pub parameters: usize,
+    /// Index of the parameter the operation writes to.
+    pub writes: Option<usize>,
+}
+
+impl Operation {
+    const fn new(
+        opcode: usize,
+        mnemonic: &'static str,
+        parameters: usize,
+        writes: Option<usize>,
+    ) -> Self {
+        Operation {
+            opcode,
+            mnemonic,
+            parameters,
+            writes,
+        }
+    }
+
+    pub fn from_opcode(opcode: usize) -> Option<Self> {
+        OPERATIONS
+            .iter()
+            .find(|operation| operation.opcode == opcode)
+            .cloned()
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Self> {
+        OPERATIONS
+            .iter()
+            .find(|operation| operation.mnemonic == mnemonic)
+            .cloned()
+    }
+}
+
+const OPERATIONS: [Operation; 10] = [
+    Operation::new(1, "add", 3, Some(2)),
+    Operation::new(2, "mul", 3, Some(2)),
+    Operation::new(3, "in", 1, Some(0)),
+    Operation::new(4, "out", 1, None),
+    Operation::new(5, "jnz", 2, None),
+    Operation::new(6, "jz", 2, None),
+    Operation::new(7, "lt", 3, Some(2)),
+    Operation::new(8, "eq", 3, Some(2)),
+    Operation::new(9, "arb", 1, None),
+    Operation::new(99, "hlt", 0, None),
+];
+
+/// Number of parameters of the longest instruction.
+pub const MAX_PARAMETERS: usize = 3;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Instruction<W = i64> {
     pub opcode: usize,
-    pub parameters: Vec<Parameter>,
+    pub parameters: Vec<Parameter<W>>,
+    pub length: usize,
+}
+
+/// Decoded instruction stored without heap allocations, as the computer
+/// executes it.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Decoded<W = i64> {
+    pub opcode: usize,
+    parameters: [Parameter<W>; MAX_PARAMETERS],
     pub length: usize,
 }
 
 #[derive(Debug, Eq, PartialEq)]
 pub enum Error {
-    InvalidOpcode { opcode: usize },
-    UnknownParameterMode { mode: usize },
+    InvalidOpcode {
+        opcode: usize,
+    },
+    UnknownParameterMode {
+        mode: usize,
+    },
+    /// The word at `position` within the instruction is not a valid opcode or address.
+    InvalidWord {
+        position: usize,
+    },
+    /// The program ends before the `length` words of the instruction.
+    Truncated {
+        length: usize,
+        available: usize,
+    },
+    /// The parameter at `position` is written to, but given in immediate mode.
+    ImmediateWrite {
+        position: usize,
+    },
+    /// The opcode specifies `modes` for parameters the operation does not take.
+    ExcessModes {
+        modes: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidOpcode { opcode } => write!(f, "invalid opcode {}", opcode),
+            Error::UnknownParameterMode { mode } => write!(f, "unknown parameter mode {}", mode),
+            Error::InvalidWord { position } => {
+                write!(
+                    f,
+                    "invalid word at position {} of the instruction",
+                    position
+                )
+            }
+            Error::Truncated { length, available } => write!(
+                f,
+                "instruction of {} words truncated after {}",
+                length, available
+            ),
+            Error::ImmediateWrite { position } => {
+                write!(f, "parameter {} is written to in immediate mode", position)
+            }
+            Error::ExcessModes { modes } => {
+                write!(f, "parameter modes {} given for missing parameters", modes)
+            }
+        }
+    }
 }
 
-impl Instruction {
-    pub fn decode(program: &[i32]) -> Result<Self, Error> {
-        let opcode = program[0] as usize;
+impl std::error::Error for Error {}
+
+impl<W> Decoded<W> {
+    pub fn parameters(&self) -> &[Parameter<W>] {
+        &self.parameters[..self.length - 1]
+    }
+}
+
+impl<W: Word> Decoded<W> {
+    /// Decodes the instruction at the start of `program`, ignoring any words after it.
+    pub fn decode(program: &[W]) -> Result<Self, Error> {
+        let opcode = program
+            .first()
+            .ok_or(Error::Truncated {
+                length: 1,
+                available: 0,
+            })?
+            .to_usize()
+            .ok_or(Error::InvalidWord { position: 0 })?;
         let parameter_modes = opcode / 100;
         let opcode = opcode % 100;
-        let parameters = match opcode {
-            1 | 2 | 7 | 8 => decode_parameters(&program[1..4], parameter_modes),
-            3 | 4 => decode_parameters(&program[1..2], parameter_modes),
-            5 | 6 => decode_parameters(&program[1..3], parameter_modes),
-            99 => Ok(vec![]),
-            _ => Err(Error::InvalidOpcode { opcode }),
-        }?;
-        let length = 1 + parameters.len();
-        Ok(Instruction {
+        let operation = Operation::from_opcode(opcode).ok_or(Error::InvalidOpcode { opcode })?;
+        let length = 1 + operation.parameters;
+        let words = program.get(1..length).ok_or(Error::Truncated {
+            length,
+            available: program.len(),
+        })?;
+        let mut parameters = [
+            Parameter::address(0),
+            Parameter::address(0),
+            Parameter::address(0),
+        ];
+        let mut modes = parameter_modes;
+        for (index, word) in words.iter().enumerate() {
+            let write = operation.writes == Some(index);
+            parameters[index] = decode_parameter(word, modes % 10, index + 1, write)?;
+            modes /= 10;
+        }
+        if modes != 0 {
+            return Err(Error::ExcessModes { modes });
+        }
+        Ok(Decoded {
             opcode,
             parameters,
             length,
@@ -48,21 +206,56 @@ impl Instruction {
     }
 }
 
-fn decode_parameters(parameters: &[i32], modes: usize) -> Result<Vec<Parameter>, Error> {
-    parameters
-        .iter()
-        .scan(modes, |modes, parameter| {
-            let item = decode_parameter(*parameter, *modes % 10);
-            *modes /= 10;
-            Some(item)
-        })
-        .collect()
+impl<W: Clone> From<Decoded<W>> for Instruction<W> {
+    fn from(decoded: Decoded<W>) -> Self {
+        Instruction {
+            opcode: decoded.opcode,
+            parameters: decoded.parameters().to_vec(),
+            length: decoded.length,
+        }
+    }
+}
+
+impl<W: Word> Instruction<W> {
+    /// Decodes the instruction at the start of `program`, ignoring any words after it.
+    pub fn decode(program: &[W]) -> Result<Self, Error> {
+        Decoded::decode(program).map(Instruction::from)
+    }
+
+    /// Encodes the instruction into words, the inverse of `decode`.
+    pub fn encode(&self) -> Vec<W> {
+        let modes: usize = self
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| parameter.mode() * 10usize.pow(index as u32 + 2))
+            .sum();
+        let mut words = vec![W::from_usize(self.opcode + modes).expect("Opcodes fit into a word")];
+        words.extend(self.parameters.iter().map(|parameter| match parameter {
+            Parameter::Address { address } => {
+                W::from_usize(*address).expect("Decoded addresses fit into a word")
+            }
+            Parameter::Value { value } => value.clone(),
+            Parameter::Relative { offset } => offset.clone(),
+        }));
+        words
+    }
 }
 
-fn decode_parameter(parameter: i32, mode: usize) -> Result<Parameter, Error> {
+fn decode_parameter<W: Word>(
+    parameter: &W,
+    mode: usize,
+    position: usize,
+    write: bool,
+) -> Result<Parameter<W>, Error> {
     match mode {
-        0 => Ok(Parameter::address(parameter as usize)),
-        1 => Ok(Parameter::value(parameter)),
+        0 => parameter
+            .to_usize()
+            .map(Parameter::address)
+            .ok_or(Error::InvalidWord { position }),
+        1 if write => Err(Error::ImmediateWrite { position }),
+        1 => Ok(Parameter::value(parameter.clone())),
+        2 => Ok(Parameter::relative(parameter.clone())),
         _ => Err(Error::UnknownParameterMode { mode }),
     }
 }
@@ -70,6 +263,10 @@ fn decode_parameter(parameter: i32, mode: usize) -> Result<Parameter, Error> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::Random;
+
+    type Instruction = super::Instruction<i64>;
+    type Parameter = super::Parameter<i64>;
 
     #[test]
     fn decode_terminate() {
@@ -108,12 +305,12 @@ mod tests {
     }
 
     #[test]
-    fn decode_input_with_value() {
+    fn decode_input_with_relative() {
         assert_eq!(
-            Instruction::decode(&[1103, 42, 2, 1, 0]),
+            Instruction::decode(&[203, 42, 2, 1, 0]),
             Ok(Instruction {
                 opcode: 3,
-                parameters: vec![Parameter::value(42)],
+                parameters: vec![Parameter::relative(42)],
                 length: 2,
             })
         );
@@ -177,4 +374,156 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    fn decode_relative_base_offset() {
+        assert_eq!(
+            Instruction::decode(&[109, 19]),
+            Ok(Instruction {
+                opcode: 9,
+                parameters: vec![Parameter::value(19)],
+                length: 2,
+            })
+        )
+    }
+
+    #[test]
+    fn decode_relative_parameters() {
+        assert_eq!(
+            Instruction::decode(&[22201, -1, 3, 0]),
+            Ok(Instruction {
+                opcode: 1,
+                parameters: vec![
+                    Parameter::relative(-1),
+                    Parameter::relative(3),
+                    Parameter::relative(0)
+                ],
+                length: 4,
+            })
+        )
+    }
+
+    #[test]
+    fn decodes_without_allocation() {
+        let decoded = Decoded::decode(&[1002i64, 4, 3, 4]).unwrap();
+        assert_eq!(decoded.opcode, 2);
+        assert_eq!(
+            decoded.parameters(),
+            &[
+                Parameter::address(4),
+                Parameter::value(3),
+                Parameter::address(4),
+            ]
+        );
+        assert!(Decoded::<i64>::decode(&[99])
+            .unwrap()
+            .parameters()
+            .is_empty());
+    }
+
+    #[test]
+    fn decode_rejects_negative_address() {
+        assert_eq!(
+            Instruction::decode(&[4, -1]),
+            Err(Error::InvalidWord { position: 1 })
+        )
+    }
+
+    #[test]
+    fn decode_rejects_negative_opcode() {
+        assert_eq!(
+            Instruction::decode(&[-99]),
+            Err(Error::InvalidWord { position: 0 })
+        )
+    }
+
+    #[test]
+    fn decode_rejects_truncated_instructions() {
+        assert_eq!(
+            Instruction::decode(&[]),
+            Err(Error::Truncated {
+                length: 1,
+                available: 0
+            })
+        );
+        assert_eq!(
+            Instruction::decode(&[1002, 4, 3]),
+            Err(Error::Truncated {
+                length: 4,
+                available: 3
+            })
+        );
+    }
+
+    #[test]
+    fn decode_rejects_immediate_writes() {
+        assert_eq!(
+            Instruction::decode(&[103, 42]),
+            Err(Error::ImmediateWrite { position: 1 })
+        );
+        assert_eq!(
+            Instruction::decode(&[11101, 1, 2, 3]),
+            Err(Error::ImmediateWrite { position: 3 })
+        );
+    }
+
+    #[test]
+    fn decode_rejects_invalid_modes() {
+        assert_eq!(
+            Instruction::decode(&[301, 1, 2, 3]),
+            Err(Error::UnknownParameterMode { mode: 3 })
+        );
+        assert_eq!(
+            Instruction::decode(&[1104, 1]),
+            Err(Error::ExcessModes { modes: 1 })
+        );
+        assert_eq!(
+            Instruction::decode(&[199]),
+            Err(Error::ExcessModes { modes: 1 })
+        );
+    }
+
+    /// Mixes edge cases, plausible instruction words and arbitrary values.
+    fn word(random: &mut Random) -> i32 {
+        match random.below(4) {
+            0 => [0, 1, -1, 99, i32::MIN, i32::MAX][random.below(6) as usize],
+            1 => {
+                (random.below(3) * 10_000 + random.below(3) * 1000 + random.below(3) * 100) as i32
+                    + [1, 2, 3, 4, 5, 6, 7, 8, 9, 99][random.below(10) as usize]
+            }
+            2 => random.below(200) as i32 - 100,
+            _ => random.next() as i32,
+        }
+    }
+
+    #[test]
+    fn decode_never_panics() {
+        let mut random = Random(0x2019_1202);
+        for _ in 0..100_000 {
+            let length = random.below(6) as usize;
+            let program: Vec<i32> = (0..length).map(|_| word(&mut random)).collect();
+            if let Ok(instruction) = super::Instruction::decode(&program) {
+                assert!(instruction.length <= program.len(), "{:?}", program);
+                assert_eq!(
+                    instruction.encode(),
+                    &program[..instruction.length],
+                    "{:?}",
+                    program
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn encode_inverts_decode() {
+        for program in &[
+            vec![1, 0, 0, 0],
+            vec![1002, 4, 3, 4],
+            vec![21107, -1, 8, 3],
+            vec![109, -19],
+            vec![99],
+        ] {
+            assert_eq!(&Instruction::decode(program).unwrap().encode(), program);
+        }
+    }
 }
@@ -0,0 +1,178 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display};
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use std::sync::mpsc::{Receiver, Sender};
+
+/// Failure of a device connected to the computer.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct DeviceError {
+    pub message: String,
+}
+
+impl DeviceError {
+    fn new(message: impl ToString) -> Self {
+        DeviceError {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Display for DeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "device error: {}", self.message)
+    }
+}
+
+impl std::error::Error for DeviceError {}
+
+/// Source of the values read by the input instruction.
+pub trait Input<W> {
+    /// Returns the next value or `None` if no value is available.
+    fn read(&mut self) -> Result<Option<W>, DeviceError>;
+}
+
+/// Sink for the values written by the output instruction.
+pub trait Output<W> {
+    fn write(&mut self, value: W) -> Result<(), DeviceError>;
+}
+
+impl<W> Input<W> for VecDeque<W> {
+    fn read(&mut self) -> Result<Option<W>, DeviceError> {
+        Ok(self.pop_front())
+    }
+}
+
+impl<W> Output<W> for VecDeque<W> {
+    fn write(&mut self, value: W) -> Result<(), DeviceError> {
+        self.push_back(value);
+        Ok(())
+    }
+}
+
+impl<W> Output<W> for Vec<W> {
+    fn write(&mut self, value: W) -> Result<(), DeviceError> {
+        self.push(value);
+        Ok(())
+    }
+}
+
+impl<W, F: FnMut() -> Option<W>> Input<W> for F {
+    fn read(&mut self) -> Result<Option<W>, DeviceError> {
+        Ok(self())
+    }
+}
+
+impl<W, F: FnMut(W)> Output<W> for F {
+    fn write(&mut self, value: W) -> Result<(), DeviceError> {
+        self(value);
+        Ok(())
+    }
+}
+
+/// Blocks until a value is received, a disconnected sender ends the input.
+impl<W> Input<W> for Receiver<W> {
+    fn read(&mut self) -> Result<Option<W>, DeviceError> {
+        Ok(self.recv().ok())
+    }
+}
+
+impl<W> Output<W> for Sender<W> {
+    fn write(&mut self, value: W) -> Result<(), DeviceError> {
+        self.send(value)
+            .map_err(|_| DeviceError::new("receiver disconnected"))
+    }
+}
+
+/// Input taking its values from an iterator.
+pub struct FromIter<I>(pub I);
+
+impl<W, I: Iterator<Item = W>> Input<W> for FromIter<I> {
+    fn read(&mut self) -> Result<Option<W>, DeviceError> {
+        Ok(self.0.next())
+    }
+}
+
+/// Text stream of values separated by whitespace or commas.
+///
+/// As input it parses values read from a `BufRead`, as output it writes one
+/// value per line to a `Write`.
+pub struct Stream<T> {
+    inner: T,
+    pending: VecDeque<String>,
+}
+
+impl<T> Stream<T> {
+    pub fn new(inner: T) -> Self {
+        Stream {
+            inner,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl<W: FromStr, R: BufRead> Input<W> for Stream<R> {
+    fn read(&mut self) -> Result<Option<W>, DeviceError> {
+        while self.pending.is_empty() {
+            let mut line = String::new();
+            if self.inner.read_line(&mut line).map_err(DeviceError::new)? == 0 {
+                return Ok(None);
+            }
+            self.pending.extend(
+                line.split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|token| !token.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        let token = self.pending.pop_front().unwrap_or_default();
+        token
+            .parse()
+            .map(Some)
+            .map_err(|_| DeviceError::new(format!("invalid value {:?}", token)))
+    }
+}
+
+impl<W: Display, T: Write> Output<W> for Stream<T> {
+    fn write(&mut self, value: W) -> Result<(), DeviceError> {
+        writeln!(self.inner, "{}", value).map_err(DeviceError::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn stream_parses_separated_values() {
+        let mut input = Stream::new("1, 2\n\n-3 4\n".as_bytes());
+        let values: Vec<Option<i64>> = (0..5).map(|_| input.read().unwrap()).collect();
+        assert_eq!(values, vec![Some(1), Some(2), Some(-3), Some(4), None]);
+    }
+
+    #[test]
+    fn stream_rejects_invalid_values() {
+        let mut input = Stream::new("x".as_bytes());
+        assert_eq!(
+            Input::<i64>::read(&mut input),
+            Err(DeviceError::new("invalid value \"x\""))
+        );
+    }
+
+    #[test]
+    fn stream_writes_lines() {
+        let mut output = Stream::new(Vec::new());
+        output.write(1i64).unwrap();
+        output.write(-2i64).unwrap();
+        assert_eq!(output.inner, b"1\n-2\n");
+    }
+
+    #[test]
+    fn channels_connect_devices() {
+        let (mut sender, mut receiver) = channel();
+        sender.write(7i64).unwrap();
+        drop(sender);
+        assert_eq!(receiver.read(), Ok(Some(7)));
+        assert_eq!(receiver.read(), Ok(None));
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+
+use super::decode::{Instruction, Operation, Parameter};
+use super::word::Word;
+
+const DATA_PER_LINE: usize = 8;
+
+/// Disassembles a program into a listing the assembler accepts.
+///
+/// Instructions are discovered by following the control flow from address 0.
+/// Jump targets given as immediate values get labels, words that are never
+/// reached are listed as data. Code that is overwritten by the program itself
+/// and instructions overlapping each other are annotated with comments.
+pub fn disassemble<W: Word>(program: &[W]) -> String {
+    let code = discover(program);
+    let targets: BTreeSet<usize> = code.values().filter_map(jump_target).collect();
+    let layout = layout(program, &code, &targets);
+    let labels: BTreeSet<usize> = targets
+        .into_iter()
+        .filter(|target| layout.contains_key(target))
+        .collect();
+    let modified = modified_code(&code, &layout);
+
+    let mut listing = String::new();
+    for (&address, line) in layout.iter() {
+        if labels.contains(&address) {
+            writeln!(listing, "L{}:", address).unwrap();
+        }
+        let (text, mut notes) = match line {
+            Line::Code { overlaps } => {
+                let instruction = &code[&address];
+                let notes = overlaps
+                    .iter()
+                    .map(|other| format!("overlaps instruction at {}", other))
+                    .collect();
+                (format_instruction(instruction, &labels), notes)
+            }
+            Line::Data { length } => (
+                format!(
+                    "db {}",
+                    program[address..address + length]
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                vec![],
+            ),
+        };
+        if let Some(writers) = modified.get(&address) {
+            notes.extend(
+                writers
+                    .iter()
+                    .map(|writer| format!("modified by {}", writer)),
+            );
+        }
+        notes.insert(0, address.to_string());
+        writeln!(listing, "    {:<32}; {}", text, notes.join(", ")).unwrap();
+    }
+    listing
+}
+
+/// Formats a single instruction, using `L<address>` for jump targets in `labels`.
+pub fn format_instruction<W: Word>(
+    instruction: &Instruction<W>,
+    labels: &BTreeSet<usize>,
+) -> String {
+    let operation = Operation::from_opcode(instruction.opcode)
+        .expect("Decoded instructions have a known opcode");
+    let target = jump_target(instruction).filter(|target| labels.contains(target));
+    let operands: Vec<String> = instruction
+        .parameters
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| operation.writes != Some(*index))
+        .map(|(index, parameter)| match target {
+            Some(target) if index == 1 => format!("#L{}", target),
+            _ => format_parameter(parameter),
+        })
+        .collect();
+    let mut text = operation.mnemonic.to_string();
+    if !operands.is_empty() {
+        text.push(' ');
+        text.push_str(&operands.join(", "));
+    }
+    if let Some(index) = operation.writes {
+        text.push_str(" -> ");
+        text.push_str(&format_parameter(&instruction.parameters[index]));
+    }
+    text
+}
+
+fn format_parameter<W: Word>(parameter: &Parameter<W>) -> String {
+    match parameter {
+        Parameter::Address { address } => format!("[{}]", address),
+        Parameter::Value { value } => format!("#{}", value),
+        Parameter::Relative { offset } if *offset < W::default() => format!("rel{}", offset),
+        Parameter::Relative { offset } => format!("rel+{}", offset),
+    }
+}
+
+enum Line {
+    Code { overlaps: Vec<usize> },
+    Data { length: usize },
+}
+
+/// Decodes the instruction at `address`, if it lies within the program and
+/// its words are exactly what the assembler would produce for it.
+fn decode_at<W: Word>(program: &[W], address: usize) -> Option<Instruction<W>> {
+    let words = program.get(address..)?;
+    let instruction = Instruction::decode(words).ok()?;
+    if instruction.encode()[..] == words[..instruction.length] {
+        Some(instruction)
+    } else {
+        None
+    }
+}
+
+fn jump_target<W: Word>(instruction: &Instruction<W>) -> Option<usize> {
+    match (instruction.opcode, instruction.parameters.get(1)) {
+        (5, Some(Parameter::Value { value })) | (6, Some(Parameter::Value { value })) => {
+            value.to_usize()
+        }
+        _ => None,
+    }
+}
+
+fn successors<W: Word>(address: usize, instruction: &Instruction<W>) -> Vec<usize> {
+    let next = address + instruction.length;
+    let condition = match instruction.parameters.first() {
+        Some(Parameter::Value { value }) => Some(!value.is_zero()),
+        _ => None,
+    };
+    match instruction.opcode {
+        99 => vec![],
+        5 | 6 => {
+            let jumps_if = instruction.opcode == 5;
+            let mut successors = vec![];
+            if condition != Some(jumps_if) {
+                successors.push(next);
+            }
+            if condition != Some(!jumps_if) {
+                successors.extend(jump_target(instruction));
+            }
+            successors
+        }
+        _ => vec![next],
+    }
+}
+
+/// Follows the control flow from address 0 and decodes every reachable instruction.
+fn discover<W: Word>(program: &[W]) -> BTreeMap<usize, Instruction<W>> {
+    let mut code = BTreeMap::new();
+    let mut visited = BTreeSet::new();
+    let mut queue = VecDeque::from(vec![0]);
+    while let Some(address) = queue.pop_front() {
+        if address >= program.len() || !visited.insert(address) {
+            continue;
+        }
+        if let Some(instruction) = decode_at(program, address) {
+            queue.extend(successors(address, &instruction));
+            code.insert(address, instruction);
+        }
+    }
+    code
+}
+
+/// Splits the program into lines of code and data, the first of overlapping
+/// instructions wins.
+fn layout<W: Word>(
+    program: &[W],
+    code: &BTreeMap<usize, Instruction<W>>,
+    targets: &BTreeSet<usize>,
+) -> BTreeMap<usize, Line> {
+    let mut layout = BTreeMap::new();
+    let mut address = 0;
+    while address < program.len() {
+        if let Some(instruction) = code.get(&address) {
+            let end = address + instruction.length;
+            let overlaps = code
+                .range(address + 1..end)
+                .map(|(&other, _)| other)
+                .collect();
+            layout.insert(address, Line::Code { overlaps });
+            address = end;
+        } else {
+            let length = (address..program.len())
+                .take(DATA_PER_LINE)
+                .take_while(|&other| {
+                    other == address || !code.contains_key(&other) && !targets.contains(&other)
+                })
+                .count();
+            layout.insert(address, Line::Data { length });
+            address += length;
+        }
+    }
+    layout
+}
+
+/// Maps the start of each instruction line to the instructions writing into it.
+fn modified_code<W: Word>(
+    code: &BTreeMap<usize, Instruction<W>>,
+    layout: &BTreeMap<usize, Line>,
+) -> BTreeMap<usize, Vec<usize>> {
+    let mut modified: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (&writer, instruction) in code.iter() {
+        let operation = Operation::from_opcode(instruction.opcode);
+        let written = operation
+            .and_then(|operation| operation.writes)
+            .map(|index| &instruction.parameters[index]);
+        if let Some(Parameter::Address { address }) = written {
+            let line = layout.range(..=address).next_back();
+            if let Some((&start, Line::Code { .. })) = line {
+                if *address < start + code[&start].length {
+                    modified.entry(start).or_default().push(writer);
+                }
+            }
+        }
+    }
+    modified
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(listing: &str) -> Vec<&str> {
+        listing.lines().map(str::trim_end).collect()
+    }
+
+    #[test]
+    fn lists_instructions_and_data() {
+        let program: [i64; 12] = [1002, 9, 3, 10, 4, 10, 204, -2, 99, 33, 0, 5];
+        assert_eq!(
+            lines(&disassemble(&program)),
+            vec![
+                "    mul [9], #3 -> [10]             ; 0",
+                "    out [10]                        ; 4",
+                "    out rel-2                       ; 6",
+                "    hlt                             ; 8",
+                "    db 33, 0, 5                     ; 9",
+            ]
+        );
+    }
+
+    #[test]
+    fn labels_jump_targets() {
+        let program: [i64; 14] = [3, 13, 1005, 13, 10, 104, 0, 1105, 1, 12, 104, 1, 99, 0];
+        assert_eq!(
+            lines(&disassemble(&program)),
+            vec![
+                "    in -> [13]                      ; 0",
+                "    jnz [13], #L10                  ; 2",
+                "    out #0                          ; 5",
+                "    jnz #1, #L12                    ; 7",
+                "L10:",
+                "    out #1                          ; 10",
+                "L12:",
+                "    hlt                             ; 12",
+                "    db 0                            ; 13",
+            ]
+        );
+    }
+
+    #[test]
+    fn annotates_overlapping_instructions() {
+        let program: [i64; 7] = [1105, 1, 2, 0, 0, 0, 99];
+        assert_eq!(
+            lines(&disassemble(&program)),
+            vec![
+                "    jnz #1, #2                      ; 0, overlaps instruction at 2, modified by 2",
+                "    db 0, 0, 0                      ; 3",
+                "    hlt                             ; 6",
+            ]
+        );
+    }
+
+    #[test]
+    fn unreachable_words_are_data() {
+        let program: [i64; 8] = [1106, 0, 7, 1, 0, 0, 0, 99];
+        assert_eq!(
+            lines(&disassemble(&program)),
+            vec![
+                "    jz #0, #L7                      ; 0",
+                "    db 1, 0, 0, 0                   ; 3",
+                "L7:",
+                "    hlt                             ; 7",
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_self_modifying_code() {
+        let program: [i64; 9] = [1, 1, 1, 4, 99, 5, 6, 0, 99];
+        assert_eq!(
+            lines(&disassemble(&program)),
+            vec![
+                "    add [1], [1] -> [4]             ; 0",
+                "    hlt                             ; 4, modified by 0",
+                "    db 5, 6, 0, 99                  ; 5",
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_and_invalid_words_are_data() {
+        let program: [i64; 3] = [1, 0, 0];
+        assert_eq!(
+            lines(&disassemble(&program)),
+            vec!["    db 1, 0, 0                      ; 0"]
+        );
+        let program: [i64; 2] = [-1, 10099];
+        assert_eq!(
+            lines(&disassemble(&program)),
+            vec!["    db -1, 10099                    ; 0"]
+        );
+    }
+}
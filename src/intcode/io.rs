@@ -1,17 +1,57 @@
-use std::io::BufRead;
-use std::num::ParseIntError;
-use std::str::Utf8Error;
+//! Reading programs from files, strings and readers.
+//!
+//! Words are separated by commas, whitespace or both, so programs may be
+//! written on one line or one word per line. Empty fields, like those left by
+//! trailing commas, are skipped and `#` starts a comment reaching to the end
+//! of the line.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::{FromStr, Utf8Error};
 
 #[derive(Debug)]
 pub enum Error {
-    ParseIntError { error: ParseIntError },
-    IoError { error: std::io::Error },
-    Utf8Error { error: Utf8Error },
+    IoError {
+        error: std::io::Error,
+    },
+    Utf8Error {
+        error: Utf8Error,
+    },
+    /// The token starting at byte `offset` is not a valid word.
+    InvalidWord {
+        offset: usize,
+        token: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::IoError { error } => write!(f, "could not read program: {}", error),
+            Error::Utf8Error { error } => write!(f, "program is not valid UTF-8: {}", error),
+            Error::InvalidWord {
+                offset,
+                token,
+                message,
+            } => write!(
+                f,
+                "invalid word {:?} at byte {}: {}",
+                token, offset, message
+            ),
+        }
+    }
 }
 
-impl From<ParseIntError> for Error {
-    fn from(error: ParseIntError) -> Self {
-        Error::ParseIntError { error }
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IoError { error } => Some(error),
+            Error::Utf8Error { error } => Some(error),
+            Error::InvalidWord { .. } => None,
+        }
     }
 }
 
@@ -27,16 +67,145 @@ impl From<Utf8Error> for Error {
     }
 }
 
-pub fn read_program() -> Result<Vec<i32>, Error> {
-    std::io::stdin()
-        .lock()
-        .split(b',')
-        .map(|bytes| {
-            bytes.map_err(Error::from).and_then(|bytes| {
-                std::str::from_utf8(&bytes)
-                    .map_err(Error::from)
-                    .and_then(|s| s.trim().parse::<i32>().map_err(Error::from))
-            })
-        })
-        .collect()
+/// Something a program can be read from, any buffered reader. Files are read
+/// with [`read_program_file`] and program text with [`parse_program`].
+pub trait Source {
+    fn read_text(self) -> Result<String, Error>;
+}
+
+impl<R: BufRead> Source for R {
+    fn read_text(mut self) -> Result<String, Error> {
+        let mut bytes = Vec::new();
+        self.read_to_end(&mut bytes)?;
+        Ok(std::str::from_utf8(&bytes)?.to_string())
+    }
+}
+
+pub fn read_program() -> Result<Vec<i64>, Error> {
+    read_program_from(std::io::stdin().lock())
+}
+
+pub fn read_program_file(path: impl AsRef<Path>) -> Result<Vec<i64>, Error> {
+    read_program_from(BufReader::new(File::open(path)?))
+}
+
+/// Reads a program from `source`, see the module documentation for the format.
+pub fn read_program_from<W, S>(source: S) -> Result<Vec<W>, Error>
+where
+    W: FromStr,
+    W::Err: fmt::Display,
+    S: Source,
+{
+    parse_program(&source.read_text()?)
+}
+
+/// Parses program text, see the module documentation for the format.
+pub fn parse_program<W>(text: &str) -> Result<Vec<W>, Error>
+where
+    W: FromStr,
+    W::Err: fmt::Display,
+{
+    let mut words = Vec::new();
+    let mut start = 0;
+    for line in text.split_inclusive('\n') {
+        let code = line.split('#').next().unwrap_or_default();
+        let mut token_start = None;
+        for (index, character) in code.char_indices().chain(Some((code.len(), ','))) {
+            let separator = character == ',' || character.is_whitespace();
+            match (token_start, separator) {
+                (None, false) => token_start = Some(index),
+                (Some(token_start_index), true) => {
+                    let token = &code[token_start_index..index];
+                    let word = token.parse().map_err(|error: W::Err| Error::InvalidWord {
+                        offset: start + token_start_index,
+                        token: token.to_string(),
+                        message: error.to_string(),
+                    })?;
+                    words.push(word);
+                    token_start = None;
+                }
+                _ => {}
+            }
+        }
+        start += line.len();
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_single_line_programs() {
+        assert_eq!(
+            parse_program::<i64>("1,9,10,3,2,3,11,0,99,30,40,50\n").unwrap(),
+            vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]
+        );
+    }
+
+    #[test]
+    fn tolerates_whitespace_trailing_commas_and_comments() {
+        let text = "# day 2 example\n1, 9,10 ,3,\n\n2,3,11,0, # multiply\r\n99,\n\t30,40,50,\n";
+        assert_eq!(
+            parse_program::<i64>(text).unwrap(),
+            vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]
+        );
+    }
+
+    #[test]
+    fn reads_newline_separated_programs() {
+        assert_eq!(
+            parse_program::<i64>("104\n-7\n99\n").unwrap(),
+            vec![104, -7, 99]
+        );
+        assert_eq!(parse_program::<i64>("").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn reports_offset_and_token() {
+        match parse_program::<i64>("1,2\n3,x4,5") {
+            Err(Error::InvalidWord { offset, token, .. }) => {
+                assert_eq!((offset, token.as_str()), (6, "x4"));
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert_eq!(
+            parse_program::<i64>("99,1e3").unwrap_err().to_string(),
+            "invalid word \"1e3\" at byte 3: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn reads_from_all_sources() {
+        let expected: Vec<i64> = vec![4, 3, 99];
+        assert_eq!(
+            read_program_from::<i64, _>(&b"4,3,99\n"[..]).unwrap(),
+            expected
+        );
+        assert_eq!(
+            read_program_from::<i64, _>(BufReader::new(&b"4\n3\n99"[..])).unwrap(),
+            expected
+        );
+        let path = std::env::temp_dir().join(format!("intcode-io-{}.txt", std::process::id()));
+        std::fs::write(&path, "4,3,99,\n").unwrap();
+        let from_file = read_program_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(from_file.unwrap(), expected);
+        assert!(matches!(
+            read_program_file("/nonexistent/program.txt"),
+            Err(Error::IoError { .. })
+        ));
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn reads_big_integers() {
+        use num_bigint::BigInt;
+        let program: Vec<BigInt> = parse_program("104,123456789012345678901234567890,99").unwrap();
+        assert_eq!(
+            program[1],
+            "123456789012345678901234567890".parse().unwrap()
+        );
+    }
 }
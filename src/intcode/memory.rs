@@ -0,0 +1,261 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::word::Word;
+
+const PAGE_SIZE: usize = 1024;
+
+/// Number of cells a computer may allocate unless configured otherwise.
+pub const DEFAULT_LIMIT: usize = 1 << 24;
+
+/// Sparse, paged memory of the Intcode computer.
+///
+/// Cells that were never written read as zero. Pages are only allocated when a
+/// non-zero value is written into them, so writes to far away addresses stay cheap.
+#[derive(Debug, Clone)]
+pub struct Memory<W> {
+    pages: BTreeMap<usize, Box<[W]>>,
+    len: usize,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    LimitExceeded {
+        limit: usize,
+    },
+    /// The length of memory cannot grow beyond the last address `usize::MAX`.
+    AddressOutOfRange {
+        address: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::LimitExceeded { limit } => write!(f, "memory limit of {} cells exceeded", limit),
+            Error::AddressOutOfRange { address } => write!(f, "address {} out of range", address),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl<W> Default for Memory<W> {
+    fn default() -> Self {
+        Memory {
+            pages: BTreeMap::new(),
+            len: 0,
+            limit: Some(DEFAULT_LIMIT),
+        }
+    }
+}
+
+impl<W: Word> Memory<W> {
+    pub fn load(&mut self, image: &[W]) {
+        self.pages.clear();
+        self.len = image.len();
+        for (index, chunk) in image.chunks(PAGE_SIZE).enumerate() {
+            let mut page = Self::empty_page();
+            page[..chunk.len()].clone_from_slice(chunk);
+            self.pages.insert(index, page);
+        }
+    }
+
+    /// Limits the number of cells that can be allocated, `None` removes the limit.
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        self.limit = limit;
+    }
+
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// One past the highest address that was loaded or written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, address: usize) -> W {
+        self.pages
+            .get(&(address / PAGE_SIZE))
+            .map(|page| page[address % PAGE_SIZE].clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set(&mut self, address: usize, value: W) -> Result<(), Error> {
+        let end = address
+            .checked_add(1)
+            .ok_or(Error::AddressOutOfRange { address })?;
+        let index = address / PAGE_SIZE;
+        if !self.pages.contains_key(&index) {
+            if value.is_zero() {
+                self.len = self.len.max(end);
+                return Ok(());
+            }
+            if let Some(limit) = self.limit {
+                if (self.pages.len() + 1) * PAGE_SIZE > limit {
+                    return Err(Error::LimitExceeded { limit });
+                }
+            }
+            self.pages.insert(index, Self::empty_page());
+        }
+        if let Some(page) = self.pages.get_mut(&index) {
+            page[address % PAGE_SIZE] = value;
+        }
+        self.len = self.len.max(end);
+        Ok(())
+    }
+
+    /// Copies all cells up to `len` into a contiguous vector.
+    pub fn to_vec(&self) -> Vec<W> {
+        (0..self.len).map(|address| self.get(address)).collect()
+    }
+
+    /// Non-zero parts of the allocated pages with the address of their first cell.
+    pub fn segments(&self) -> Vec<(usize, &[W])> {
+        self.pages
+            .iter()
+            .filter_map(|(index, page)| {
+                let start = index * PAGE_SIZE;
+                let page = &page[..self.len.saturating_sub(start).min(PAGE_SIZE)];
+                let first = page.iter().position(|value| !value.is_zero())?;
+                let last = page.iter().rposition(|value| !value.is_zero())?;
+                Some((start + first, &page[first..=last]))
+            })
+            .collect()
+    }
+
+    /// Rebuilds memory of `len` cells with `limit` from the output of
+    /// `segments`, which must lie within the `len` cells.
+    pub fn from_segments<'a>(
+        len: usize,
+        limit: Option<usize>,
+        segments: impl IntoIterator<Item = (usize, &'a [W])>,
+    ) -> Result<Self, Error>
+    where
+        W: 'a,
+    {
+        let mut memory = Memory {
+            limit,
+            ..Memory::default()
+        };
+        for (start, values) in segments {
+            match start.checked_add(values.len()) {
+                Some(end) if end <= len => {}
+                _ => return Err(Error::AddressOutOfRange { address: start }),
+            }
+            for (address, value) in (start..).zip(values) {
+                memory.set(address, value.clone())?;
+            }
+        }
+        memory.len = memory.len.max(len);
+        Ok(memory)
+    }
+
+    fn empty_page() -> Box<[W]> {
+        vec![W::default(); PAGE_SIZE].into_boxed_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unwritten_cells_read_as_zero() {
+        let mut memory = Memory::default();
+        memory.load(&[1i64, 2, 3]);
+        assert_eq!(memory.get(2), 3);
+        assert_eq!(memory.get(3), 0);
+        assert_eq!(memory.get(1_000_000_000), 0);
+        assert_eq!(memory.len(), 3);
+    }
+
+    #[test]
+    fn grows_on_write() {
+        let mut memory = Memory::default();
+        memory.load(&[1i64, 2, 3]);
+        assert_eq!(memory.set(5, 7), Ok(()));
+        assert_eq!(memory.to_vec(), vec![1, 2, 3, 0, 0, 7]);
+    }
+
+    #[test]
+    fn far_writes_allocate_single_page() {
+        let mut memory = Memory::default();
+        assert_eq!(memory.set(usize::MAX / 2, 42i64), Ok(()));
+        assert_eq!(memory.get(usize::MAX / 2), 42);
+        assert_eq!(memory.pages.len(), 1);
+    }
+
+    #[test]
+    fn rebuilds_from_segments() {
+        let mut memory = Memory::default();
+        memory.load(&[0i64, 1, 2, 0, 3, 0, 0]);
+        memory.set(PAGE_SIZE * 5 + 3, 4).unwrap();
+        memory.set(PAGE_SIZE * 9, 0).unwrap();
+        assert_eq!(
+            memory.segments(),
+            vec![(1, &[1, 2, 0, 3][..]), (PAGE_SIZE * 5 + 3, &[4][..])]
+        );
+        let restored = Memory::from_segments(memory.len(), None, memory.segments()).unwrap();
+        assert_eq!(restored.len(), PAGE_SIZE * 9 + 1);
+        assert_eq!(restored.to_vec(), memory.to_vec());
+        assert_eq!(restored.limit(), None);
+    }
+
+    #[test]
+    fn rejects_segments_out_of_range() {
+        let values = [1i64, 2];
+        assert_eq!(
+            Memory::from_segments(10, None, vec![(usize::MAX, &values[..])]).map(|_| ()),
+            Err(Error::AddressOutOfRange {
+                address: usize::MAX
+            })
+        );
+        assert_eq!(
+            Memory::from_segments(10, None, vec![(9, &values[..])]).map(|_| ()),
+            Err(Error::AddressOutOfRange { address: 9 })
+        );
+        assert_eq!(
+            Memory::from_segments(
+                4096,
+                Some(PAGE_SIZE),
+                vec![(0, &values[..]), (2048, &values[..])]
+            )
+            .map(|_| ()),
+            Err(Error::LimitExceeded { limit: PAGE_SIZE })
+        );
+    }
+
+    #[test]
+    fn enforces_limit() {
+        let mut memory = Memory::default();
+        memory.set_limit(Some(PAGE_SIZE));
+        assert_eq!(memory.set(0, 1i64), Ok(()));
+        assert_eq!(memory.set(PAGE_SIZE - 1, 1), Ok(()));
+        assert_eq!(
+            memory.set(PAGE_SIZE, 1),
+            Err(Error::LimitExceeded { limit: PAGE_SIZE })
+        );
+        assert_eq!(memory.set(PAGE_SIZE, 0), Ok(()));
+    }
+
+    #[test]
+    fn rejects_last_address() {
+        let mut memory = Memory::default();
+        assert_eq!(
+            memory.set(usize::MAX, 1i64),
+            Err(Error::AddressOutOfRange {
+                address: usize::MAX
+            })
+        );
+        assert_eq!(memory.get(usize::MAX), 0);
+        assert_eq!(memory.set(usize::MAX - 1, 1), Ok(()));
+        assert_eq!(memory.len(), usize::MAX);
+    }
+}
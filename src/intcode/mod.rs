@@ -1,6 +1,22 @@
+pub mod asm;
+mod cache;
 mod computer;
-mod decode;
+pub mod debugger;
+pub mod decode;
+pub mod device;
+pub mod disasm;
 pub mod io;
+mod memory;
+pub mod search;
+pub mod snapshot;
+pub mod symbolic;
+pub mod trace;
+mod word;
 
 pub use computer::Computer;
 pub use computer::Error;
+pub use computer::Fault;
+pub use computer::Status;
+pub use device::{Input, Output};
+pub use memory::Memory;
+pub use word::Word;
@@ -0,0 +1,340 @@
+//! Search for the noun and verb inputs producing a given output, as in day 2.
+//!
+//! The noun and verb are written to addresses 1 and 2 before running the
+//! program, the output is read from address 0 after it halts.
+
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use super::Computer;
+
+/// Output of a program that is affine in noun and verb.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Affine {
+    pub constant: i64,
+    pub noun: i64,
+    pub verb: i64,
+}
+
+impl Affine {
+    pub fn evaluate(&self, noun: i64, verb: i64) -> Option<i64> {
+        self.noun
+            .checked_mul(noun)?
+            .checked_add(self.verb.checked_mul(verb)?)?
+            .checked_add(self.constant)
+    }
+}
+
+/// Search over noun and verb ranges, by default `0..=99` each.
+///
+/// Candidates failing to execute are skipped. Results are ordered by noun,
+/// then by verb.
+pub struct NounVerbSearch<'a> {
+    program: &'a [i64],
+    nouns: RangeInclusive<i64>,
+    verbs: RangeInclusive<i64>,
+    threads: usize,
+}
+
+impl<'a> NounVerbSearch<'a> {
+    pub fn new(program: &'a [i64]) -> Self {
+        NounVerbSearch {
+            program,
+            nouns: 0..=99,
+            verbs: 0..=99,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+
+    pub fn nouns(mut self, nouns: RangeInclusive<i64>) -> Self {
+        self.nouns = nouns;
+        self
+    }
+
+    pub fn verbs(mut self, verbs: RangeInclusive<i64>) -> Self {
+        self.verbs = verbs;
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Output of the program for `noun` and `verb`, `None` if execution fails.
+    pub fn output(&self, noun: i64, verb: i64) -> Option<i64> {
+        Computer::execute_with_memory_io(self.program, noun, verb).ok()
+    }
+
+    /// Finds the first pair producing `target` by running all candidates.
+    pub fn find(&self, target: i64) -> Option<(i64, i64)> {
+        let found = AtomicUsize::new(usize::MAX);
+        self.scan(|index, noun, verb| {
+            if index > found.load(Ordering::Relaxed) {
+                return false;
+            }
+            if self.output(noun, verb) == Some(target) {
+                found.fetch_min(index, Ordering::Relaxed);
+                return false;
+            }
+            true
+        });
+        match found.into_inner() {
+            usize::MAX => None,
+            index => Some(self.candidate(index)),
+        }
+    }
+
+    /// Finds all pairs producing `target` by running all candidates.
+    pub fn find_all(&self, target: i64) -> Vec<(i64, i64)> {
+        let found = Mutex::new(Vec::new());
+        self.scan(|index, noun, verb| {
+            if self.output(noun, verb) == Some(target) {
+                found.lock().unwrap().push(index);
+            }
+            true
+        });
+        let mut found = found.into_inner().unwrap();
+        found.sort_unstable();
+        found
+            .into_iter()
+            .map(|index| self.candidate(index))
+            .collect()
+    }
+
+    /// Finds a pair producing `target`, solving for it directly if the output
+    /// looks affine in noun and verb.
+    ///
+    /// The affine fit only samples a few candidates, so the pair it predicts
+    /// is run to confirm it. If there is no such pair or it produces another
+    /// output, all candidates are searched for the first pair. A confirmed
+    /// prediction is returned even if an earlier pair also produces `target`,
+    /// use `find` for the first one.
+    pub fn solve(&self, target: i64) -> Option<(i64, i64)> {
+        let predicted = self
+            .affine()
+            .and_then(|affine| self.predict(&affine, target));
+        match predicted {
+            Some((noun, verb)) if self.output(noun, verb) == Some(target) => Some((noun, verb)),
+            _ => self.find(target),
+        }
+    }
+
+    /// First pair in the ranges for which `affine` evaluates to `target`.
+    fn predict(&self, affine: &Affine, target: i64) -> Option<(i64, i64)> {
+        self.nouns
+            .clone()
+            .filter_map(|noun| {
+                let rest = target
+                    .checked_sub(affine.constant)?
+                    .checked_sub(affine.noun.checked_mul(noun)?)?;
+                match affine.verb {
+                    0 if rest == 0 => Some((noun, *self.verbs.start())),
+                    0 => None,
+                    factor if rest.checked_rem(factor)? == 0 => {
+                        Some((noun, rest.checked_div(factor)?))
+                    }
+                    _ => None,
+                }
+            })
+            .find(|(_, verb)| self.verbs.contains(verb))
+    }
+
+    /// Fits an affine function through the outputs at the corners of the
+    /// ranges and checks it at the center.
+    pub fn affine(&self) -> Option<Affine> {
+        let (noun_start, noun_end) = (*self.nouns.start(), *self.nouns.end());
+        let (verb_start, verb_end) = (*self.verbs.start(), *self.verbs.end());
+        if noun_start > noun_end || verb_start > verb_end {
+            return None;
+        }
+        let origin = self.output(noun_start, verb_start)?;
+        let slope = |output: i64, distance: i64| {
+            let change = output.checked_sub(origin)?;
+            match distance {
+                0 => Some(0),
+                _ if change % distance == 0 => Some(change / distance),
+                _ => None,
+            }
+        };
+        let noun = slope(
+            self.output(noun_end, verb_start)?,
+            noun_end.checked_sub(noun_start)?,
+        )?;
+        let verb = slope(
+            self.output(noun_start, verb_end)?,
+            verb_end.checked_sub(verb_start)?,
+        )?;
+        let constant = origin
+            .checked_sub(noun.checked_mul(noun_start)?)?
+            .checked_sub(verb.checked_mul(verb_start)?)?;
+        let affine = Affine {
+            constant,
+            noun,
+            verb,
+        };
+        let center = (
+            midpoint(noun_start, noun_end),
+            midpoint(verb_start, verb_end),
+        );
+        [(noun_end, verb_end), center]
+            .iter()
+            .all(|&(noun, verb)| {
+                let output = self.output(noun, verb);
+                output.is_some() && output == affine.evaluate(noun, verb)
+            })
+            .then_some(affine)
+    }
+
+    /// Number of candidates, saturating at `usize::MAX`.
+    fn candidates(&self) -> usize {
+        count(&self.nouns).saturating_mul(count(&self.verbs))
+    }
+
+    fn candidate(&self, index: usize) -> (i64, i64) {
+        let verbs = count(&self.verbs);
+        let offset = |start: i64, offset: usize| (i128::from(start) + offset as i128) as i64;
+        (
+            offset(*self.nouns.start(), index / verbs),
+            offset(*self.verbs.start(), index % verbs),
+        )
+    }
+
+    /// Calls `visit` for the candidates in order, spread over the threads,
+    /// until it returns false.
+    fn scan(&self, visit: impl Fn(usize, i64, i64) -> bool + Sync) {
+        let candidates = self.candidates();
+        let threads = self.threads.min(candidates.max(1));
+        let visit = &visit;
+        thread::scope(|scope| {
+            for first in 0..threads {
+                scope.spawn(move || {
+                    for index in (first..candidates).step_by(threads) {
+                        let (noun, verb) = self.candidate(index);
+                        if !visit(index, noun, verb) {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
+
+/// Number of values in `range`, saturating at `usize::MAX`.
+fn count(range: &RangeInclusive<i64>) -> usize {
+    let count = i128::from(*range.end()) - i128::from(*range.start()) + 1;
+    usize::try_from(count.max(0)).unwrap_or(usize::MAX)
+}
+
+fn midpoint(start: i64, end: i64) -> i64 {
+    ((i128::from(start) + i128::from(end)) / 2) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Leaves `5 * noun + verb + 7` in address 0.
+    const AFFINE: [i64; 22] = [
+        1, 0, 0, 3, 2, 1, 20, 3, 1, 3, 2, 3, 1, 3, 21, 0, 99, 0, 0, 0, 5, 7,
+    ];
+
+    /// Adds the cells noun and verb point to.
+    const LOOKUP: [i64; 8] = [1, 0, 0, 0, 99, 10, 20, 35];
+
+    /// Adds the cells noun and verb point to, which for nouns and verbs in
+    /// `5..=9` matches `10 * noun + 10 * verb - 100` at the corners and the
+    /// center only.
+    const ALMOST_AFFINE: [i64; 10] = [1, 0, 0, 0, 99, 0, 3, 20, 30, 40];
+
+    #[test]
+    fn detects_affine_programs() {
+        assert_eq!(
+            NounVerbSearch::new(&AFFINE).affine(),
+            Some(Affine {
+                constant: 7,
+                noun: 5,
+                verb: 1
+            })
+        );
+        assert_eq!(NounVerbSearch::new(&LOOKUP).affine(), None);
+    }
+
+    #[test]
+    fn finds_first_solution() {
+        let search = NounVerbSearch::new(&AFFINE);
+        assert_eq!(search.find(69), Some((0, 62)));
+        assert_eq!(search.solve(69), Some((0, 62)));
+        assert_eq!(search.find(1000), None);
+        assert_eq!(search.solve(1000), None);
+    }
+
+    #[test]
+    fn finds_all_solutions() {
+        let solutions = NounVerbSearch::new(&AFFINE).threads(3).find_all(69);
+        assert_eq!(solutions.len(), 13);
+        assert_eq!(solutions.first(), Some(&(0, 62)));
+        assert_eq!(solutions.last(), Some(&(12, 2)));
+    }
+
+    #[test]
+    fn searches_non_affine_programs() {
+        let search = NounVerbSearch::new(&LOOKUP).nouns(5..=7).verbs(5..=7);
+        assert_eq!(search.solve(55), Some((6, 7)));
+        assert_eq!(search.find_all(55), vec![(6, 7), (7, 6)]);
+    }
+
+    #[test]
+    fn falls_back_if_affine_fit_is_wrong() {
+        let search = NounVerbSearch::new(&ALMOST_AFFINE)
+            .nouns(5..=9)
+            .verbs(5..=9);
+        assert_eq!(
+            search.affine(),
+            Some(Affine {
+                constant: -100,
+                noun: 10,
+                verb: 10
+            })
+        );
+        // No pair is predicted to produce 33.
+        assert_eq!(search.solve(33), Some((6, 8)));
+        // The predicted pair (6, 9) produces 43 instead.
+        assert_eq!(search.solve(50), Some((7, 8)));
+        assert_eq!(search.solve(51), None);
+    }
+
+    #[test]
+    fn returns_confirmed_prediction_before_earlier_pairs() {
+        // Fits the same function as ALMOST_AFFINE, but (5, 6) produces 30 too.
+        let program = [1, 0, 0, 0, 99, 0, 30, 20, 30, 40];
+        let search = NounVerbSearch::new(&program).nouns(5..=9).verbs(5..=9);
+        assert_eq!(search.solve(30), Some((5, 8)));
+        assert_eq!(search.find(30), Some((5, 6)));
+    }
+
+    #[test]
+    fn handles_extreme_ranges() {
+        let search = NounVerbSearch::new(&AFFINE)
+            .nouns(i64::MIN..=i64::MAX)
+            .verbs(i64::MIN..=i64::MAX);
+        assert_eq!(search.candidates(), usize::MAX);
+        assert_eq!(search.candidate(usize::MAX - 1), (i64::MIN, i64::MAX - 1));
+        assert_eq!(search.affine(), None);
+        let search = NounVerbSearch::new(&AFFINE).nouns(RangeInclusive::new(i64::MAX, i64::MIN));
+        assert_eq!(search.candidates(), 0);
+        assert_eq!(search.find(0), None);
+    }
+
+    #[test]
+    fn skips_failing_candidates() {
+        let search = NounVerbSearch::new(&AFFINE).nouns(-3..=3).verbs(0..=10);
+        assert_eq!(search.affine(), None);
+        assert_eq!(search.find_all(17), vec![(0, 10), (1, 5), (2, 0)]);
+        assert_eq!(search.solve(17), Some((0, 10)));
+    }
+}
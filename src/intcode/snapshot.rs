@@ -0,0 +1,282 @@
+//! Serializable state of a computer.
+//!
+//! Snapshots are stored as text, one field per line:
+//!
+//! ```text
+//! intcode-snapshot 1
+//! ip 4
+//! relative-base 0
+//! steps 1
+//! recent 0
+//! input 7,8
+//! output 42
+//! memory 9
+//! limit 16777216
+//! segment 0 1,0,0,3,99
+//! ```
+//!
+//! Memory is listed as segments of non-zero cells, so the size of a snapshot
+//! does not depend on how far away the program writes. A limit of `none`
+//! means memory is unlimited. Snapshots without `steps`, `recent` or `limit`
+//! lines start with no history and the default memory limit.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use super::memory::{self, DEFAULT_LIMIT};
+
+const HEADER: &str = "intcode-snapshot 1";
+
+/// State of a computer with its pending input and collected output.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Snapshot<W = i64> {
+    pub instruction_pointer: usize,
+    pub relative_base: W,
+    /// Number of instructions executed so far.
+    pub steps: usize,
+    /// Addresses of the most recently executed instructions, oldest first.
+    pub recent: Vec<usize>,
+    pub input: Vec<W>,
+    pub output: Vec<W>,
+    /// Number of memory cells, including trailing zeros.
+    pub memory_len: usize,
+    pub memory_limit: Option<usize>,
+    /// Non-zero memory segments with the address of their first cell.
+    pub memory: Vec<(usize, Vec<W>)>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IoError {
+        error: std::io::Error,
+    },
+    UnsupportedFormat,
+    InvalidLine {
+        line: usize,
+    },
+    MissingField {
+        name: &'static str,
+    },
+    /// The memory segment starting at `address` exceeds the memory size.
+    InvalidSegment {
+        address: usize,
+    },
+    MemoryLimit {
+        limit: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::IoError { error } => write!(f, "{}", error),
+            Error::UnsupportedFormat => write!(f, "not a snapshot, expected {:?}", HEADER),
+            Error::InvalidLine { line } => write!(f, "invalid snapshot line {}", line),
+            Error::MissingField { name } => write!(f, "snapshot lacks {}", name),
+            Error::InvalidSegment { address } => {
+                write!(f, "memory segment at {} exceeds the memory size", address)
+            }
+            Error::MemoryLimit { limit } => {
+                write!(f, "memory exceeds the limit of {} cells", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::IoError { error }
+    }
+}
+
+impl From<memory::Error> for Error {
+    fn from(error: memory::Error) -> Self {
+        match error {
+            memory::Error::LimitExceeded { limit } => Error::MemoryLimit { limit },
+            memory::Error::AddressOutOfRange { address } => Error::InvalidSegment { address },
+        }
+    }
+}
+
+impl<W: fmt::Display> fmt::Display for Snapshot<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "ip {}", self.instruction_pointer)?;
+        writeln!(f, "relative-base {}", self.relative_base)?;
+        writeln!(f, "steps {}", self.steps)?;
+        writeln!(f, "recent {}", join(&self.recent))?;
+        writeln!(f, "input {}", join(&self.input))?;
+        writeln!(f, "output {}", join(&self.output))?;
+        writeln!(f, "memory {}", self.memory_len)?;
+        match self.memory_limit {
+            Some(limit) => writeln!(f, "limit {}", limit)?,
+            None => writeln!(f, "limit none")?,
+        }
+        for (address, values) in self.memory.iter() {
+            writeln!(f, "segment {} {}", address, join(values))?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: FromStr> FromStr for Snapshot<W> {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Error> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line));
+        if lines.next().map(|(_, line)| line.trim()) != Some(HEADER) {
+            return Err(Error::UnsupportedFormat);
+        }
+        let mut instruction_pointer = None;
+        let mut relative_base = None;
+        let mut steps = 0;
+        let mut recent = Vec::new();
+        let mut input = None;
+        let mut output = None;
+        let mut memory_len = None;
+        let mut memory_limit = Some(DEFAULT_LIMIT);
+        let mut memory = Vec::new();
+        for (line, text) in lines {
+            let invalid = || Error::InvalidLine { line };
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+            let (key, value) = match text.find(' ') {
+                Some(index) => (&text[..index], text[index + 1..].trim()),
+                None => (text, ""),
+            };
+            match key {
+                "ip" => instruction_pointer = Some(value.parse().map_err(|_| invalid())?),
+                "relative-base" => relative_base = Some(value.parse().map_err(|_| invalid())?),
+                "steps" => steps = value.parse().map_err(|_| invalid())?,
+                "recent" => recent = split(value).ok_or_else(invalid)?,
+                "input" => input = Some(split(value).ok_or_else(invalid)?),
+                "output" => output = Some(split(value).ok_or_else(invalid)?),
+                "memory" => memory_len = Some(value.parse().map_err(|_| invalid())?),
+                "limit" if value == "none" => memory_limit = None,
+                "limit" => memory_limit = Some(value.parse().map_err(|_| invalid())?),
+                "segment" => {
+                    let (address, values) = value.split_at(value.find(' ').ok_or_else(invalid)?);
+                    let values = split(values.trim()).ok_or_else(invalid)?;
+                    memory.push((address.parse().map_err(|_| invalid())?, values));
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        let missing = |name| Error::MissingField { name };
+        Ok(Snapshot {
+            instruction_pointer: instruction_pointer.ok_or_else(|| missing("ip"))?,
+            relative_base: relative_base.ok_or_else(|| missing("relative-base"))?,
+            steps,
+            recent,
+            input: input.ok_or_else(|| missing("input"))?,
+            output: output.ok_or_else(|| missing("output"))?,
+            memory_len: memory_len.ok_or_else(|| missing("memory"))?,
+            memory_limit,
+            memory,
+        })
+    }
+}
+
+impl<W: fmt::Display> Snapshot<W> {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+}
+
+impl<W: FromStr> Snapshot<W> {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+fn join<W: fmt::Display>(values: &[W]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn split<W: FromStr>(values: &str) -> Option<Vec<W>> {
+    if values.is_empty() {
+        return Some(vec![]);
+    }
+    values
+        .split(',')
+        .map(|value| value.trim().parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            instruction_pointer: 4,
+            relative_base: -2,
+            steps: 3,
+            recent: vec![0, 2, 4],
+            input: vec![7, 8],
+            output: vec![],
+            memory_len: 2000,
+            memory_limit: None,
+            memory: vec![(0, vec![1, 0, 0, 3, 99]), (1500, vec![-5])],
+        }
+    }
+
+    #[test]
+    fn formats_as_text() {
+        assert_eq!(
+            snapshot().to_string(),
+            "intcode-snapshot 1\nip 4\nrelative-base -2\nsteps 3\nrecent 0,2,4\ninput 7,8\n\
+             output \nmemory 2000\nlimit none\nsegment 0 1,0,0,3,99\nsegment 1500 -5\n"
+        );
+    }
+
+    #[test]
+    fn parses_formatted_snapshot() {
+        assert_eq!(
+            snapshot().to_string().parse::<Snapshot>().unwrap(),
+            snapshot()
+        );
+    }
+
+    #[test]
+    fn defaults_missing_history_and_limit() {
+        let snapshot: Snapshot = "intcode-snapshot 1\nip 0\nrelative-base 0\ninput \n\
+                                  output \nmemory 1\nsegment 0 99"
+            .parse()
+            .unwrap();
+        assert_eq!((snapshot.steps, snapshot.recent), (0, vec![]));
+        assert_eq!(snapshot.memory_limit, Some(DEFAULT_LIMIT));
+    }
+
+    #[test]
+    fn rejects_invalid_snapshots() {
+        assert!(matches!(
+            "ip 4".parse::<Snapshot>(),
+            Err(Error::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            "intcode-snapshot 1\nip x".parse::<Snapshot>(),
+            Err(Error::InvalidLine { line: 2 })
+        ));
+        assert!(matches!(
+            "intcode-snapshot 1\nip 4\n".parse::<Snapshot>(),
+            Err(Error::MissingField {
+                name: "relative-base"
+            })
+        ));
+    }
+}
@@ -0,0 +1,823 @@
+//! Symbolic execution of Intcode programs.
+//!
+//! Chosen memory cells hold named symbols instead of numbers. Arithmetic and
+//! comparisons on symbols build expressions, jumps depending on symbols fork
+//! the execution into one path per outcome. Execution stops with an error as
+//! soon as an instruction, a written address or a jump target would depend on
+//! a symbol, as those cannot be followed without knowing the values. A jump
+//! on a symbolic condition to such a target only drops the path taking it,
+//! unless the other outcome contradicts the path.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::decode::{self, Operation};
+use super::memory::DEFAULT_LIMIT;
+
+const DEFAULT_STEP_LIMIT: usize = 100_000;
+const DEFAULT_PATH_LIMIT: usize = 64;
+
+/// Value of a memory cell in terms of the symbols.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Expr {
+    Constant {
+        value: i64,
+    },
+    Symbol {
+        name: String,
+    },
+    /// Initial content of the cell at a symbolic address.
+    Load {
+        address: Box<Expr>,
+    },
+    Add {
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Multiply {
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    LessThan {
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    Equals {
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+}
+
+/// Sum of symbolic terms multiplied with factors, plus a constant.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Linear {
+    pub terms: BTreeMap<Expr, i64>,
+    pub constant: i64,
+}
+
+impl Expr {
+    pub fn constant(value: i64) -> Self {
+        Expr::Constant { value }
+    }
+
+    pub fn symbol(name: impl ToString) -> Self {
+        Expr::Symbol {
+            name: name.to_string(),
+        }
+    }
+
+    /// The value of the expression if it does not depend on any symbol.
+    pub fn value(&self) -> Option<i64> {
+        match self {
+            Expr::Constant { value } => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn load(address: Expr) -> Self {
+        Expr::Load {
+            address: Box::new(address),
+        }
+    }
+
+    fn add(left: Expr, right: Expr) -> Self {
+        match (left.value(), right.value()) {
+            (Some(a), Some(b)) if a.checked_add(b).is_some() => Expr::constant(a + b),
+            (Some(0), _) => right,
+            (_, Some(0)) => left,
+            _ => Expr::Add {
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+        }
+    }
+
+    fn multiply(left: Expr, right: Expr) -> Self {
+        match (left.value(), right.value()) {
+            (Some(a), Some(b)) if a.checked_mul(b).is_some() => Expr::constant(a * b),
+            (Some(0), _) | (_, Some(0)) => Expr::constant(0),
+            (Some(1), _) => right,
+            (_, Some(1)) => left,
+            _ => Expr::Multiply {
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+        }
+    }
+
+    fn less_than(left: Expr, right: Expr) -> Self {
+        match (left.value(), right.value()) {
+            (Some(a), Some(b)) => Expr::constant((a < b) as i64),
+            _ if left == right => Expr::constant(0),
+            _ => Expr::LessThan {
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+        }
+    }
+
+    fn equals(left: Expr, right: Expr) -> Self {
+        match (left.value(), right.value()) {
+            (Some(a), Some(b)) => Expr::constant((a == b) as i64),
+            _ if left == right => Expr::constant(1),
+            _ => Expr::Equals {
+                left: Box::new(left),
+                right: Box::new(right),
+            },
+        }
+    }
+
+    /// Rewrites the expression as a linear combination of symbols and loads,
+    /// if it is one.
+    pub fn linear(&self) -> Option<Linear> {
+        match self {
+            Expr::Constant { value } => Some(Linear {
+                terms: BTreeMap::new(),
+                constant: *value,
+            }),
+            Expr::Symbol { .. } | Expr::Load { .. } => Some(Linear {
+                terms: vec![(self.clone(), 1)].into_iter().collect(),
+                constant: 0,
+            }),
+            Expr::Add { left, right } => {
+                let mut sum = left.linear()?;
+                let right = right.linear()?;
+                for (term, factor) in right.terms {
+                    let entry = sum.terms.entry(term).or_insert(0);
+                    *entry = entry.checked_add(factor)?;
+                }
+                sum.terms.retain(|_, factor| *factor != 0);
+                sum.constant = sum.constant.checked_add(right.constant)?;
+                Some(sum)
+            }
+            Expr::Multiply { left, right } => {
+                let (mut product, factor) = match (left.value(), right.value()) {
+                    (Some(factor), _) => (right.linear()?, factor),
+                    (_, Some(factor)) => (left.linear()?, factor),
+                    _ => return None,
+                };
+                for value in product.terms.values_mut() {
+                    *value = value.checked_mul(factor)?;
+                }
+                product.constant = product.constant.checked_mul(factor)?;
+                Some(product)
+            }
+            Expr::LessThan { .. } | Expr::Equals { .. } => None,
+        }
+    }
+
+    fn format_operand(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Constant { .. } | Expr::Symbol { .. } | Expr::Load { .. } => {
+                write!(f, "{}", self)
+            }
+            _ => write!(f, "({})", self),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Expr::Add { .. } | Expr::Multiply { .. } = self {
+            if let Some(linear) = self.linear().filter(|linear| !linear.terms.is_empty()) {
+                return write!(f, "{}", linear);
+            }
+        }
+        let (left, operator, right) = match self {
+            Expr::Constant { value } => return write!(f, "{}", value),
+            Expr::Symbol { name } => return write!(f, "{}", name),
+            Expr::Load { address } => return write!(f, "mem[{}]", address),
+            Expr::Add { left, right } => match right.value() {
+                Some(value) if value < 0 => {
+                    left.format_operand(f)?;
+                    return write!(f, " - {}", value.unsigned_abs());
+                }
+                _ => (left, "+", right),
+            },
+            Expr::Multiply { left, right } => (left, "*", right),
+            Expr::LessThan { left, right } => (left, "<", right),
+            Expr::Equals { left, right } => (left, "==", right),
+        };
+        left.format_operand(f)?;
+        write!(f, " {} ", operator)?;
+        right.format_operand(f)
+    }
+}
+
+impl fmt::Display for Linear {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for (term, factor) in self.terms.iter() {
+            let sign = if *factor < 0 { "-" } else { "+" };
+            match (first, sign) {
+                (true, "-") => write!(f, "-")?,
+                (true, _) => {}
+                (false, sign) => write!(f, " {} ", sign)?,
+            }
+            match factor.unsigned_abs() {
+                1 => term.format_operand(f)?,
+                factor => {
+                    write!(f, "{} * ", factor)?;
+                    term.format_operand(f)?;
+                }
+            }
+            first = false;
+        }
+        match (first, self.constant) {
+            (true, constant) => write!(f, "{}", constant),
+            (false, 0) => Ok(()),
+            (false, constant) if constant < 0 => write!(f, " - {}", constant.unsigned_abs()),
+            (false, constant) => write!(f, " + {}", constant),
+        }
+    }
+}
+
+/// Outcome of a jump on a symbolic condition along a path.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Constraint {
+    pub condition: Expr,
+    /// Whether the condition is non-zero on the path.
+    pub holds: bool,
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.condition, self.holds) {
+            (Expr::LessThan { left, right }, true) => write!(f, "{} < {}", left, right),
+            (Expr::LessThan { left, right }, false) => write!(f, "{} >= {}", left, right),
+            (Expr::Equals { left, right }, true) => write!(f, "{} == {}", left, right),
+            (Expr::Equals { left, right }, false) => write!(f, "{} != {}", left, right),
+            (condition, true) => write!(f, "{} != 0", condition),
+            (condition, false) => write!(f, "{} == 0", condition),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Error {
+    InstructionError {
+        error: decode::Error,
+    },
+    /// The word at `address` is executed as an instruction, but is symbolic.
+    SymbolicInstruction {
+        address: usize,
+    },
+    /// The instruction at `instruction` writes to, jumps to or adjusts the
+    /// relative base by a symbolic value.
+    SymbolicAddress {
+        instruction: usize,
+    },
+    AddressOutOfRange {
+        address: i64,
+    },
+    /// The instruction at `instruction` moves the relative base out of range.
+    Overflow {
+        instruction: usize,
+    },
+    MemoryLimit {
+        limit: usize,
+    },
+    MissingInput,
+    StepLimit {
+        limit: usize,
+    },
+    PathLimit {
+        limit: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InstructionError { error } => write!(f, "{}", error),
+            Error::SymbolicInstruction { address } => write!(
+                f,
+                "the instruction at {} depends on symbolic values, execute concretely instead",
+                address
+            ),
+            Error::SymbolicAddress { instruction } => write!(
+                f,
+                "the instruction at {} uses a symbolic address, execute concretely instead",
+                instruction
+            ),
+            Error::AddressOutOfRange { address } => write!(f, "address {} out of range", address),
+            Error::Overflow { instruction } => {
+                write!(f, "overflow in the instruction at {}", instruction)
+            }
+            Error::MemoryLimit { limit } => {
+                write!(f, "memory limit of {} cells exceeded", limit)
+            }
+            Error::MissingInput => write!(f, "missing input"),
+            Error::StepLimit { limit } => write!(f, "a path exceeds {} steps", limit),
+            Error::PathLimit { limit } => {
+                write!(f, "execution forks into more than {} paths", limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<decode::Error> for Error {
+    fn from(error: decode::Error) -> Self {
+        Error::InstructionError { error }
+    }
+}
+
+/// A way through the program from the start to a halt instruction.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Path {
+    /// Outcomes of the symbolic jumps taken, in execution order.
+    pub constraints: Vec<Constraint>,
+    pub outputs: Vec<Expr>,
+    memory: BTreeMap<usize, Expr>,
+}
+
+impl Path {
+    /// Content of the memory cell at `address` after the program halted.
+    pub fn get(&self, address: usize) -> Expr {
+        get(&self.memory, address)
+    }
+}
+
+/// Interpreter running a program on symbolic memory.
+pub struct SymbolicComputer {
+    initial: State,
+    step_limit: usize,
+    path_limit: usize,
+    memory_limit: usize,
+}
+
+impl SymbolicComputer {
+    pub fn new(program: &[i64]) -> Self {
+        SymbolicComputer {
+            initial: State {
+                memory: BTreeMap::new(),
+                written: false,
+                instruction_pointer: 0,
+                relative_base: 0,
+                input: VecDeque::new(),
+                outputs: Vec::new(),
+                constraints: Vec::new(),
+                steps: 0,
+            },
+            step_limit: DEFAULT_STEP_LIMIT,
+            path_limit: DEFAULT_PATH_LIMIT,
+            memory_limit: DEFAULT_LIMIT,
+        }
+        .load(program)
+    }
+
+    fn load(mut self, program: &[i64]) -> Self {
+        for (address, value) in program.iter().enumerate() {
+            self.initial.store(address, Expr::constant(*value));
+        }
+        self
+    }
+
+    /// Replaces the memory cell at `address` with the symbol `name`.
+    pub fn set_symbol(&mut self, address: usize, name: impl ToString) {
+        self.initial.store(address, Expr::symbol(name));
+    }
+
+    pub fn set(&mut self, address: usize, value: Expr) {
+        self.initial.store(address, value);
+    }
+
+    /// Queues a value, possibly symbolic, for the next input instruction.
+    pub fn push_input(&mut self, value: Expr) {
+        self.initial.input.push_back(value);
+    }
+
+    /// Limits the number of instructions executed along each path.
+    pub fn set_step_limit(&mut self, limit: usize) {
+        self.step_limit = limit;
+    }
+
+    /// Limits the number of paths, each symbolic jump may add one.
+    pub fn set_path_limit(&mut self, limit: usize) {
+        self.path_limit = limit;
+    }
+
+    /// Limits the number of non-zero memory cells along each path.
+    pub fn set_memory_limit(&mut self, limit: usize) {
+        self.memory_limit = limit;
+    }
+
+    /// Follows all paths through the program depth first and returns them in
+    /// the order they halt. At a symbolic jump, the path taking the jump is
+    /// followed before the one that does not.
+    pub fn run(&self) -> Result<Vec<Path>, Error> {
+        let mut pending = vec![self.initial.clone()];
+        let mut forks = 1;
+        let mut paths = Vec::new();
+        while let Some(mut state) = pending.pop() {
+            loop {
+                if state.steps == self.step_limit {
+                    return Err(Error::StepLimit {
+                        limit: self.step_limit,
+                    });
+                }
+                match state.step(self.memory_limit)? {
+                    Step::Continue => {}
+                    Step::Fork(other) => {
+                        forks += 1;
+                        if forks > self.path_limit {
+                            return Err(Error::PathLimit {
+                                limit: self.path_limit,
+                            });
+                        }
+                        pending.push(*other);
+                    }
+                    Step::Infeasible => break,
+                    Step::Halted => {
+                        paths.push(Path {
+                            constraints: state.constraints,
+                            outputs: state.outputs,
+                            memory: state.memory,
+                        });
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(paths)
+    }
+}
+
+#[derive(Clone)]
+struct State {
+    /// Non-zero memory cells, the others hold zero.
+    memory: BTreeMap<usize, Expr>,
+    /// Whether the program wrote to memory, after which loads from symbolic
+    /// addresses no longer refer to the initial memory.
+    written: bool,
+    instruction_pointer: usize,
+    relative_base: i64,
+    input: VecDeque<Expr>,
+    outputs: Vec<Expr>,
+    constraints: Vec<Constraint>,
+    steps: usize,
+}
+
+enum Step {
+    Continue,
+    Fork(Box<State>),
+    Infeasible,
+    Halted,
+}
+
+fn get(memory: &BTreeMap<usize, Expr>, address: usize) -> Expr {
+    memory
+        .get(&address)
+        .cloned()
+        .unwrap_or_else(|| Expr::constant(0))
+}
+
+impl State {
+    fn get(&self, address: usize) -> Expr {
+        get(&self.memory, address)
+    }
+
+    fn store(&mut self, address: usize, value: Expr) {
+        if value.value() == Some(0) {
+            self.memory.remove(&address);
+        } else {
+            self.memory.insert(address, value);
+        }
+    }
+
+    fn step(&mut self, memory_limit: usize) -> Result<Step, Error> {
+        let ip = self.instruction_pointer;
+        let word = self
+            .get(ip)
+            .value()
+            .ok_or(Error::SymbolicInstruction { address: ip })?;
+        let opcode =
+            usize::try_from(word).map_err(|_| decode::Error::InvalidWord { position: 0 })?;
+        let operation =
+            Operation::from_opcode(opcode % 100).ok_or(decode::Error::InvalidOpcode {
+                opcode: opcode % 100,
+            })?;
+        let modes: Vec<usize> = (0..operation.parameters)
+            .map(|index| opcode / 10usize.pow(index as u32 + 2) % 10)
+            .collect();
+        if opcode / 10usize.pow(operation.parameters as u32 + 2) != 0 {
+            return Err(decode::Error::ExcessModes {
+                modes: opcode / 10usize.pow(operation.parameters as u32 + 2),
+            }
+            .into());
+        }
+        let next = ip + 1 + operation.parameters;
+        self.steps += 1;
+        let mut step = Step::Continue;
+        match operation.opcode {
+            1 | 2 | 7 | 8 => {
+                let left = self.operand(&modes, 0)?;
+                let right = self.operand(&modes, 1)?;
+                let value = match operation.opcode {
+                    1 => Expr::add(left, right),
+                    2 => Expr::multiply(left, right),
+                    7 => Expr::less_than(left, right),
+                    _ => Expr::equals(left, right),
+                };
+                let target = self.target(&modes, 2)?;
+                self.write(target, value, memory_limit)?;
+            }
+            3 => {
+                let value = self.input.pop_front().ok_or(Error::MissingInput)?;
+                let target = self.target(&modes, 0)?;
+                self.write(target, value, memory_limit)?;
+            }
+            4 => {
+                let value = self.operand(&modes, 0)?;
+                self.outputs.push(value);
+            }
+            5 | 6 => {
+                let condition = self.operand(&modes, 0)?;
+                let target = self.operand(&modes, 1)?;
+                let jumps_if = operation.opcode == 5;
+                // Resolved before moving on, so errors name this instruction.
+                let destination = self.concrete_address(&target);
+                self.instruction_pointer = next;
+                match condition.value() {
+                    Some(value) => {
+                        if (value != 0) == jumps_if {
+                            self.instruction_pointer = destination?;
+                        }
+                    }
+                    None => {
+                        let mut other = self.clone();
+                        let not_taken = other.assume(condition.clone(), !jumps_if);
+                        let taken = self.assume(condition, jumps_if)
+                            && match destination {
+                                Ok(destination) => {
+                                    self.instruction_pointer = destination;
+                                    true
+                                }
+                                // Only the path taking the jump cannot be
+                                // followed.
+                                Err(error) if !not_taken => return Err(error),
+                                Err(_) => false,
+                            };
+                        return Ok(match (taken, not_taken) {
+                            (true, true) => Step::Fork(Box::new(other)),
+                            (false, true) => {
+                                *self = other;
+                                Step::Continue
+                            }
+                            (true, false) => Step::Continue,
+                            (false, false) => Step::Infeasible,
+                        });
+                    }
+                }
+                return Ok(Step::Continue);
+            }
+            9 => {
+                let offset = self.operand(&modes, 0)?;
+                let offset = offset
+                    .value()
+                    .ok_or(Error::SymbolicAddress { instruction: ip })?;
+                self.relative_base = self
+                    .relative_base
+                    .checked_add(offset)
+                    .ok_or(Error::Overflow { instruction: ip })?;
+            }
+            _ => step = Step::Halted,
+        }
+        if let Step::Continue = step {
+            self.instruction_pointer = next;
+        }
+        Ok(step)
+    }
+
+    /// Records the outcome of a symbolic condition, returns false if it
+    /// contradicts an earlier outcome.
+    fn assume(&mut self, condition: Expr, holds: bool) -> bool {
+        if self
+            .constraints
+            .iter()
+            .any(|constraint| constraint.condition == condition && constraint.holds != holds)
+        {
+            return false;
+        }
+        self.constraints.push(Constraint { condition, holds });
+        true
+    }
+
+    fn write(&mut self, address: usize, value: Expr, limit: usize) -> Result<(), Error> {
+        if self.memory.len() >= limit && !self.memory.contains_key(&address) {
+            return Err(Error::MemoryLimit { limit });
+        }
+        self.store(address, value);
+        self.written = true;
+        Ok(())
+    }
+
+    fn word(&self, index: usize) -> Expr {
+        self.get(self.instruction_pointer + 1 + index)
+    }
+
+    fn concrete_address(&self, address: &Expr) -> Result<usize, Error> {
+        let address = address.value().ok_or(Error::SymbolicAddress {
+            instruction: self.instruction_pointer,
+        })?;
+        usize::try_from(address).map_err(|_| Error::AddressOutOfRange { address })
+    }
+
+    fn relative(&self, offset: &Expr) -> Result<Expr, Error> {
+        let offset = offset.value().ok_or(Error::SymbolicAddress {
+            instruction: self.instruction_pointer,
+        })?;
+        let address = self
+            .relative_base
+            .checked_add(offset)
+            .ok_or(Error::Overflow {
+                instruction: self.instruction_pointer,
+            })?;
+        Ok(Expr::constant(address))
+    }
+
+    fn operand(&self, modes: &[usize], index: usize) -> Result<Expr, Error> {
+        let word = self.word(index);
+        let address = match modes[index] {
+            0 => word,
+            1 => return Ok(word),
+            2 => self.relative(&word)?,
+            mode => return Err(decode::Error::UnknownParameterMode { mode }.into()),
+        };
+        match address.value() {
+            Some(_) => Ok(self.get(self.concrete_address(&address)?)),
+            None if !self.written => Ok(Expr::load(address)),
+            None => Err(Error::SymbolicAddress {
+                instruction: self.instruction_pointer,
+            }),
+        }
+    }
+
+    fn target(&self, modes: &[usize], index: usize) -> Result<usize, Error> {
+        let word = self.word(index);
+        match modes[index] {
+            0 => self.concrete_address(&word),
+            1 => Err(decode::Error::ImmediateWrite {
+                position: index + 1,
+            }
+            .into()),
+            2 => self.concrete_address(&self.relative(&word)?),
+            mode => Err(decode::Error::UnknownParameterMode { mode }.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day2_style(program: &[i64]) -> SymbolicComputer {
+        let mut computer = SymbolicComputer::new(program);
+        computer.set_symbol(1, "noun");
+        computer.set_symbol(2, "verb");
+        computer
+    }
+
+    #[test]
+    fn derives_affine_formula() {
+        let program = [
+            1, 0, 0, 3, 2, 1, 20, 3, 1, 3, 2, 3, 1, 3, 21, 0, 99, 0, 0, 0, 5, 7,
+        ];
+        let paths = day2_style(&program).run().unwrap();
+        assert_eq!(paths.len(), 1);
+        assert_eq!(paths[0].get(0).to_string(), "5 * noun + verb + 7");
+        assert_eq!(
+            paths[0].get(0).linear().map(|linear| linear.constant),
+            Some(7)
+        );
+    }
+
+    #[test]
+    fn keeps_non_linear_expressions() {
+        let program = [2, 0, 0, 0, 1, 0, 9, 0, 99, -3];
+        let paths = day2_style(&program).run().unwrap();
+        assert_eq!(paths[0].get(0).to_string(), "(mem[noun] * mem[verb]) - 3");
+    }
+
+    #[test]
+    fn forks_on_symbolic_jumps() {
+        // out #1 if [1] < 10, else out #2
+        let program = [
+            1107, 0, 10, 20, 1006, 20, 12, 104, 1, 1105, 1, 14, 104, 2, 99,
+        ];
+        let mut computer = SymbolicComputer::new(&program);
+        computer.set_symbol(1, "x");
+        let paths = computer.run().unwrap();
+        let outcomes: Vec<(String, Vec<Expr>)> = paths
+            .iter()
+            .map(|path| {
+                let constraints: Vec<String> =
+                    path.constraints.iter().map(ToString::to_string).collect();
+                (constraints.join(", "), path.outputs.clone())
+            })
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("x >= 10".to_string(), vec![Expr::constant(2)]),
+                ("x < 10".to_string(), vec![Expr::constant(1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn prunes_contradicting_paths() {
+        // Tests x twice, taking the second jump only on the path taking the first.
+        let mut computer = SymbolicComputer::new(&[1005, 9, 3, 1005, 9, 8, 104, 1, 99, 0]);
+        computer.set_symbol(9, "x");
+        let outputs: Vec<Vec<Expr>> = computer
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(|path| path.outputs)
+            .collect();
+        assert_eq!(outputs, vec![vec![], vec![Expr::constant(1)]]);
+    }
+
+    #[test]
+    fn reports_symbolic_addresses() {
+        let mut computer = day2_style(&[1101, 0, 0, 5, 1, 0, 0, 0, 99]);
+        assert_eq!(
+            computer.run(),
+            Err(Error::SymbolicAddress { instruction: 4 })
+        );
+        computer.set(0, Expr::symbol("opcode"));
+        assert_eq!(
+            computer.run(),
+            Err(Error::SymbolicInstruction { address: 0 })
+        );
+    }
+
+    #[test]
+    fn reports_symbolic_jump_targets() {
+        let mut computer = SymbolicComputer::new(&[105, 1, 4, 99, 0]);
+        computer.set_symbol(4, "target");
+        assert_eq!(
+            computer.run(),
+            Err(Error::SymbolicAddress { instruction: 0 })
+        );
+        // Jumps to a symbolic target on any path testing x twice.
+        let mut computer = SymbolicComputer::new(&[1005, 9, 3, 5, 9, 10, 104, 1, 99, 0, 0]);
+        computer.set_symbol(9, "x");
+        computer.set_symbol(10, "target");
+        assert_eq!(
+            computer.run(),
+            Err(Error::SymbolicAddress { instruction: 3 })
+        );
+    }
+
+    #[test]
+    fn keeps_path_not_taking_symbolic_jump() {
+        let mut computer = SymbolicComputer::new(&[5, 7, 8, 104, 1, 99, 0, 0, 0]);
+        computer.set_symbol(7, "x");
+        computer.set_symbol(8, "target");
+        let outputs: Vec<Vec<Expr>> = computer
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(|path| path.outputs)
+            .collect();
+        assert_eq!(outputs, vec![vec![Expr::constant(1)]]);
+    }
+
+    #[test]
+    fn limits_forks_and_steps() {
+        let mut computer = SymbolicComputer::new(&[1005, 3, 0, 0, 99]);
+        computer.set_symbol(3, "x");
+        computer.set_step_limit(50);
+        assert_eq!(computer.run(), Err(Error::StepLimit { limit: 50 }));
+        let mut computer = SymbolicComputer::new(&[3, 100, 1005, 100, 0, 99]);
+        for index in 0..10 {
+            computer.push_input(Expr::symbol(format!("x{}", index)));
+        }
+        computer.set_path_limit(4);
+        assert_eq!(computer.run(), Err(Error::PathLimit { limit: 4 }));
+    }
+
+    #[test]
+    fn writes_far_away_sparsely() {
+        let paths = SymbolicComputer::new(&[1101, 1, 1, 1_000_000_000_000, 99])
+            .run()
+            .unwrap();
+        assert_eq!(paths[0].get(1_000_000_000_000), Expr::constant(2));
+        let mut computer = SymbolicComputer::new(&[1101, 1, 1, 1000, 1101, 1, 1, 2000, 99]);
+        computer.set_memory_limit(10);
+        assert_eq!(computer.run(), Err(Error::MemoryLimit { limit: 10 }));
+    }
+
+    #[test]
+    fn reports_relative_base_overflow() {
+        let computer = SymbolicComputer::new(&[109, i64::MAX, 109, 1, 99]);
+        assert_eq!(computer.run(), Err(Error::Overflow { instruction: 2 }));
+        let computer = SymbolicComputer::new(&[109, i64::MAX, 204, 1, 99]);
+        assert_eq!(computer.run(), Err(Error::Overflow { instruction: 2 }));
+    }
+}
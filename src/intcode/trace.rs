@@ -0,0 +1,357 @@
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+use std::rc::Rc;
+
+use super::decode::Instruction;
+use super::disasm::format_instruction;
+use super::word::Word;
+
+const BINARY_MAGIC: &[u8; 4] = b"ICT1";
+
+/// An executed instruction as seen by a tracer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Record<W = i64> {
+    pub address: usize,
+    pub instruction: Instruction<W>,
+    /// Values of the parameters the instruction reads, in parameter order.
+    pub operands: Vec<W>,
+    /// Memory cells written by the instruction and their new values.
+    pub writes: Vec<(usize, W)>,
+}
+
+/// Observer receiving every instruction the computer executes.
+pub trait Tracer<W> {
+    fn trace(&mut self, record: &Record<W>);
+}
+
+impl<W: Clone> Tracer<W> for Vec<Record<W>> {
+    fn trace(&mut self, record: &Record<W>) {
+        self.push(record.clone());
+    }
+}
+
+/// Shares a tracer, so it can be inspected while a computer owns it.
+impl<W, T: Tracer<W>> Tracer<W> for Rc<RefCell<T>> {
+    fn trace(&mut self, record: &Record<W>) {
+        self.borrow_mut().trace(record);
+    }
+}
+
+/// Keeps the first error of a sink, as tracers cannot report errors to the computer.
+struct Sink<T> {
+    inner: T,
+    error: Option<io::Error>,
+}
+
+impl<T: Write> Sink<T> {
+    fn new(inner: T) -> Self {
+        Sink { inner, error: None }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        if self.error.is_none() {
+            if let Err(error) = self.inner.write_all(bytes) {
+                self.error = Some(error);
+            }
+        }
+    }
+
+    fn finish(mut self) -> io::Result<T> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.inner.flush().map(|_| self.inner),
+        }
+    }
+}
+
+/// Writes one human-readable line per instruction.
+pub struct LogTracer<T: Write> {
+    sink: Sink<T>,
+}
+
+impl<T: Write> LogTracer<T> {
+    pub fn new(inner: T) -> Self {
+        LogTracer {
+            sink: Sink::new(inner),
+        }
+    }
+
+    pub fn finish(self) -> io::Result<T> {
+        self.sink.finish()
+    }
+}
+
+impl<W: Word, T: Write> Tracer<W> for LogTracer<T> {
+    fn trace(&mut self, record: &Record<W>) {
+        let mut line = format!(
+            "{:>6}: {:<32}",
+            record.address,
+            format_instruction(&record.instruction, &BTreeSet::new())
+        );
+        if !record.operands.is_empty() {
+            let operands: Vec<String> = record.operands.iter().map(W::to_string).collect();
+            line.push_str(&format!(" reads {}", operands.join(", ")));
+        }
+        for (address, value) in record.writes.iter() {
+            line.push_str(&format!(" [{}] = {}", address, value));
+        }
+        self.sink.write(line.trim_end().as_bytes());
+        self.sink.write(b"\n");
+    }
+}
+
+/// Writes one JSON object per instruction and line.
+pub struct JsonTracer<T: Write> {
+    sink: Sink<T>,
+}
+
+impl<T: Write> JsonTracer<T> {
+    pub fn new(inner: T) -> Self {
+        JsonTracer {
+            sink: Sink::new(inner),
+        }
+    }
+
+    pub fn finish(self) -> io::Result<T> {
+        self.sink.finish()
+    }
+}
+
+impl<W: Word, T: Write> Tracer<W> for JsonTracer<T> {
+    fn trace(&mut self, record: &Record<W>) {
+        let words: Vec<String> = record
+            .instruction
+            .encode()
+            .iter()
+            .map(W::to_string)
+            .collect();
+        let operands: Vec<String> = record.operands.iter().map(W::to_string).collect();
+        let writes: Vec<String> = record
+            .writes
+            .iter()
+            .map(|(address, value)| format!("{{\"address\":{},\"value\":{}}}", address, value))
+            .collect();
+        let line = format!(
+            "{{\"ip\":{},\"opcode\":{},\"words\":[{}],\"text\":\"{}\",\"operands\":[{}],\"writes\":[{}]}}\n",
+            record.address,
+            record.instruction.opcode,
+            words.join(","),
+            format_instruction(&record.instruction, &BTreeSet::new()),
+            operands.join(","),
+            writes.join(",")
+        );
+        self.sink.write(line.as_bytes());
+    }
+}
+
+/// Writes a compact binary trace, read back with `read_binary_trace`.
+///
+/// Numbers are stored as LEB128 varints, signed values zigzag encoded.
+pub struct BinaryTracer<T: Write> {
+    sink: Sink<T>,
+}
+
+impl<T: Write> BinaryTracer<T> {
+    pub fn new(inner: T) -> Self {
+        let mut sink = Sink::new(inner);
+        sink.write(BINARY_MAGIC);
+        BinaryTracer { sink }
+    }
+
+    pub fn finish(self) -> io::Result<T> {
+        self.sink.finish()
+    }
+
+    fn unsigned(&mut self, mut value: u128) {
+        let mut bytes = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                break;
+            }
+            bytes.push(byte | 0x80);
+        }
+        self.sink.write(&bytes);
+    }
+
+    fn signed(&mut self, value: i128) {
+        self.unsigned(((value << 1) ^ (value >> 127)) as u128);
+    }
+
+    fn words<W: Copy + Into<i128>>(&mut self, words: &[W]) {
+        self.unsigned(words.len() as u128);
+        for word in words {
+            self.signed((*word).into());
+        }
+    }
+}
+
+impl<W: Word + Copy + Into<i128>, T: Write> Tracer<W> for BinaryTracer<T> {
+    fn trace(&mut self, record: &Record<W>) {
+        self.unsigned(record.address as u128);
+        self.words(&record.instruction.encode());
+        self.words(&record.operands);
+        self.unsigned(record.writes.len() as u128);
+        for (address, value) in record.writes.iter() {
+            self.unsigned(*address as u128);
+            self.signed((*value).into());
+        }
+    }
+}
+
+/// Reads a trace written by a `BinaryTracer`.
+pub fn read_binary_trace<R: Read>(reader: R) -> io::Result<Vec<Record<i64>>> {
+    let mut bytes = io::BufReader::new(reader).bytes();
+    let mut magic = [0; 4];
+    for byte in magic.iter_mut() {
+        *byte = bytes
+            .next()
+            .unwrap_or_else(|| Err(invalid("missing header")))?;
+    }
+    if &magic != BINARY_MAGIC {
+        return Err(invalid("not a binary trace"));
+    }
+    let mut bytes = bytes.peekable();
+    let mut records = Vec::new();
+    while bytes.peek().is_some() {
+        let mut unsigned = || -> io::Result<u128> {
+            let mut value = 0u128;
+            for shift in (0..128).step_by(7) {
+                let byte = bytes.next().unwrap_or_else(|| Err(invalid("truncated")))?;
+                value |= u128::from(byte & 0x7f) << shift;
+                if byte & 0x80 == 0 {
+                    return Ok(value);
+                }
+            }
+            Err(invalid("varint too long"))
+        };
+        let address = to_usize(unsigned()?)?;
+        let mut words = Vec::new();
+        let mut operands = Vec::new();
+        for values in [&mut words, &mut operands].iter_mut() {
+            for _ in 0..unsigned()? {
+                values.push(to_word(unsigned()?)?);
+            }
+        }
+        let mut writes = Vec::new();
+        for _ in 0..unsigned()? {
+            writes.push((to_usize(unsigned()?)?, to_word(unsigned()?)?));
+        }
+        let instruction =
+            Instruction::decode(&words).map_err(|_| invalid("invalid instruction"))?;
+        records.push(Record {
+            address,
+            instruction,
+            operands,
+            writes,
+        });
+    }
+    Ok(records)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn to_usize(value: u128) -> io::Result<usize> {
+    usize::try_from(value).map_err(|_| invalid("address out of range"))
+}
+
+fn to_word(value: u128) -> io::Result<i64> {
+    let value = (value >> 1) as i128 ^ -((value & 1) as i128);
+    i64::try_from(value).map_err(|_| invalid("value out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::Computer;
+
+    const PROGRAM: [i64; 11] = [3, 9, 1002, 9, -3, 10, 4, 10, 99, 0, 0];
+
+    fn traced<T: Tracer<i64> + 'static>(tracer: T) -> Rc<RefCell<T>> {
+        let tracer = Rc::new(RefCell::new(tracer));
+        let mut computer = Computer::new(&PROGRAM[..]);
+        computer.push_input(5);
+        computer.set_tracer(Box::new(tracer.clone()));
+        computer.run().expect("Execution failed");
+        tracer
+    }
+
+    fn unwrap<T>(tracer: Rc<RefCell<T>>) -> T {
+        Rc::try_unwrap(tracer).ok().unwrap().into_inner()
+    }
+
+    #[test]
+    fn records_operands_and_writes() {
+        let records = unwrap(traced(Vec::new()));
+        let summary: Vec<_> = records
+            .iter()
+            .map(|record| {
+                (
+                    record.address,
+                    record.operands.clone(),
+                    record.writes.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, vec![], vec![(9, 5)]),
+                (2, vec![5, -3], vec![(10, -15)]),
+                (6, vec![-15], vec![]),
+                (8, vec![], vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn logs_lines() {
+        let log = unwrap(traced(LogTracer::new(Vec::new()))).finish().unwrap();
+        assert_eq!(
+            String::from_utf8(log).unwrap(),
+            "     0: in -> [9]                        [9] = 5
+     2: mul [9], #-3 -> [10]             reads 5, -3 [10] = -15
+     6: out [10]                         reads -15
+     8: hlt
+"
+        );
+    }
+
+    #[test]
+    fn writes_json_lines() {
+        let json = unwrap(traced(JsonTracer::new(Vec::new())))
+            .finish()
+            .unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(
+            json.lines().nth(1),
+            Some(
+                "{\"ip\":2,\"opcode\":2,\"words\":[1002,9,-3,10],\"text\":\"mul [9], #-3 -> [10]\",\
+                 \"operands\":[5,-3],\"writes\":[{\"address\":10,\"value\":-15}]}"
+            )
+        );
+        assert_eq!(json.lines().count(), 4);
+    }
+
+    #[test]
+    fn binary_trace_round_trips() {
+        let bytes = unwrap(traced(BinaryTracer::new(Vec::new())))
+            .finish()
+            .unwrap();
+        assert_eq!(bytes.len(), 38);
+        let records = read_binary_trace(&bytes[..]).unwrap();
+        assert_eq!(records, unwrap(traced(Vec::new())));
+    }
+
+    #[test]
+    fn rejects_foreign_binary_traces() {
+        assert!(read_binary_trace(&b"ICT0"[..]).is_err());
+        assert!(read_binary_trace(&b"ICT1\x80"[..]).is_err());
+    }
+}
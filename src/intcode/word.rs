@@ -0,0 +1,102 @@
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
+
+/// A value stored in a memory cell of the Intcode computer.
+///
+/// Arithmetic is checked, so a program exceeding the range of the word type
+/// fails with an error instead of silently wrapping around.
+pub trait Word: Clone + Debug + Display + Default + Ord + FromStr + From<i32> {
+    fn checked_add(&self, other: &Self) -> Option<Self>;
+
+    fn checked_mul(&self, other: &Self) -> Option<Self>;
+
+    /// Converts the word into an address or opcode, if it is non-negative and fits.
+    fn to_usize(&self) -> Option<usize>;
+
+    fn from_usize(value: usize) -> Option<Self>;
+
+    fn from_bool(value: bool) -> Self {
+        Self::from(value as i32)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+macro_rules! primitive_word {
+    ($($word:ty),*) => {
+        $(
+            impl Word for $word {
+                fn checked_add(&self, other: &Self) -> Option<Self> {
+                    <$word>::checked_add(*self, *other)
+                }
+
+                fn checked_mul(&self, other: &Self) -> Option<Self> {
+                    <$word>::checked_mul(*self, *other)
+                }
+
+                fn to_usize(&self) -> Option<usize> {
+                    use std::convert::TryFrom;
+                    usize::try_from(*self).ok()
+                }
+
+                fn from_usize(value: usize) -> Option<Self> {
+                    use std::convert::TryFrom;
+                    <$word>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+primitive_word!(i32, i64, i128);
+
+#[cfg(feature = "bigint")]
+impl Word for num_bigint::BigInt {
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        Some(self + other)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        Some(self * other)
+    }
+
+    fn to_usize(&self) -> Option<usize> {
+        use std::convert::TryFrom;
+        usize::try_from(self).ok()
+    }
+
+    fn from_usize(value: usize) -> Option<Self> {
+        Some(Self::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitive_arithmetic_is_checked() {
+        assert_eq!(Word::checked_add(&i32::MAX, &1), None);
+        assert_eq!(Word::checked_mul(&i64::MAX, &2), None);
+        assert_eq!(Word::checked_add(&40i64, &2), Some(42));
+    }
+
+    #[test]
+    fn only_non_negative_words_are_addresses() {
+        assert_eq!(Word::to_usize(&7i64), Some(7));
+        assert_eq!(Word::to_usize(&-1i64), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn big_integers_do_not_overflow() {
+        use num_bigint::BigInt;
+        let big = BigInt::from(i64::MAX);
+        assert_eq!(
+            Word::checked_mul(&big, &big),
+            Some(BigInt::from(i128::from(i64::MAX) * i128::from(i64::MAX)))
+        );
+    }
+}
@@ -1 +1,4 @@
+pub mod days;
 pub mod intcode;
+#[cfg(test)]
+mod random;
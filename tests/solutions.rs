@@ -0,0 +1,70 @@
+//! Runs the registered solutions on the puzzle examples through the same
+//! code path as the `aoc` runner.
+
+use std::path::Path;
+
+use aoc_2019::days::answers::{Answers, Check};
+use aoc_2019::days::{self, Error, SOLVERS};
+
+const WIRES: &str = "R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83\n";
+
+const ORBITS: &str = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN\n";
+
+#[test]
+fn solves_examples() {
+    let cases = [
+        (1, 1, "12\n14\n1969\n100756\n", "34241"),
+        (1, 2, "14\n1969\n100756\n", "51314"),
+        (3, 1, WIRES, "159"),
+        (3, 2, WIRES, "610"),
+        (4, 1, "111111-111122", "10"),
+        (5, 1, "3,0,4,0,99", "1"),
+        (6, 1, ORBITS, "54"),
+        (6, 2, ORBITS, "4"),
+        (
+            7,
+            1,
+            "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0",
+            "43210",
+        ),
+    ];
+    for &(day, part, input, answer) in cases.iter() {
+        assert_eq!(
+            days::solve(day, part, input).unwrap(),
+            answer,
+            "day {} part {}",
+            day,
+            part
+        );
+    }
+}
+
+#[test]
+fn reports_invalid_input() {
+    for (day, solver) in SOLVERS.iter() {
+        for part in 1..=2 {
+            assert!(
+                solver.solve(part, "not an input").is_err(),
+                "day {} part {}",
+                day,
+                part
+            );
+        }
+    }
+    assert!(matches!(
+        days::solve(6, 2, "COM)B\n"),
+        Err(Error::InvalidInput { .. })
+    ));
+}
+
+#[test]
+fn matches_committed_answers() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("answers");
+    for (day, _) in SOLVERS.iter() {
+        let checks = Answers::load(*day, Answers::path(&directory, *day))
+            .and_then(|answers| answers.verify())
+            .unwrap_or_else(|error| panic!("day {}: {}", day, error));
+        assert_eq!(checks.len(), 2, "day {}", day);
+        assert!(checks.iter().all(Check::passed), "{:?}", checks);
+    }
+}